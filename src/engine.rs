@@ -1,4 +1,14 @@
+pub mod card;
 pub mod cards;
 pub mod constants;
+pub mod deck;
+pub mod evaluator;
+pub mod indexer;
+pub mod isomorphism;
+pub mod monte_carlo;
 pub mod out_runner;
 pub mod outcome;
+pub mod range;
+
+#[cfg(test)]
+mod verification;
@@ -0,0 +1,289 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::cards::{Cards, CardsError};
+use super::constants::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// All ranks from low to high.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Rank::ALL.get(index as usize).copied()
+    }
+
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The mask of all four cards of this rank, see [RANKS].
+    pub fn value(&self) -> u64 {
+        RANKS[RANKS.len() - 1 - self.index() as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        RANK_NAMES[RANK_NAMES.len() - 1 - self.index() as usize]
+    }
+}
+
+impl TryFrom<char> for Rank {
+    type Error = CardsError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        let mut buffer = [0; 4];
+        let name = value.encode_utf8(&mut buffer);
+
+        Rank::ALL
+            .into_iter()
+            .find(|rank| rank.name().eq_ignore_ascii_case(name))
+            .ok_or(CardsError::InvalidRank(value))
+    }
+}
+
+impl FromStr for Rank {
+    type Err = CardsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Rank::try_from(c),
+            (Some(c), Some(_)) => Err(CardsError::InvalidRank(c)),
+            (None, _) => Err(CardsError::Empty),
+        }
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Suit {
+    Club,
+    Diamond,
+    Heart,
+    Spade,
+}
+
+impl Suit {
+    /// All suits in the order of their bits within a rank, from low to high.
+    pub const ALL: [Suit; 4] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Suit::ALL.get(index as usize).copied()
+    }
+
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The mask of all thirteen cards of this suit, see [SUITS].
+    pub fn value(&self) -> u64 {
+        SUITS[SUITS.len() - 1 - self.index() as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        SUIT_NAMES[SUIT_NAMES.len() - 1 - self.index() as usize]
+    }
+}
+
+impl TryFrom<char> for Suit {
+    type Error = CardsError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        let mut buffer = [0; 4];
+        let name = value.encode_utf8(&mut buffer);
+
+        Suit::ALL
+            .into_iter()
+            .find(|suit| suit.name().eq_ignore_ascii_case(name))
+            .ok_or(CardsError::InvalidSuit(value))
+    }
+}
+
+impl FromStr for Suit {
+    type Err = CardsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Suit::try_from(c),
+            (Some(c), Some(_)) => Err(CardsError::InvalidSuit(c)),
+            (None, _) => Err(CardsError::Empty),
+        }
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A single card, stored as the index of its bit in the [Cards] layout.
+///
+/// The index is `rank * 4 + suit`, so `2c` is 0 and `As` is 51. Ordering is by rank first, then suit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card(u8);
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card(rank.index() * 4 + suit.index())
+    }
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        (index < 52).then_some(Card(index))
+    }
+
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank::ALL[(self.0 / 4) as usize]
+    }
+
+    pub fn suit(&self) -> Suit {
+        Suit::ALL[(self.0 % 4) as usize]
+    }
+
+    /// The single bit of this card in the [Cards] layout.
+    pub fn value(&self) -> u64 {
+        1 << self.0
+    }
+}
+
+impl From<Card> for Cards {
+    fn from(value: Card) -> Self {
+        Cards::from(value.value())
+    }
+}
+
+impl TryFrom<Cards> for Card {
+    type Error = CardsError;
+
+    fn try_from(value: Cards) -> Result<Self, Self::Error> {
+        if value.card_count() == 1 && value.is_valid() {
+            Ok(Card(value.value().trailing_zeros() as u8))
+        } else {
+            Err(CardsError::InvalidCards(value))
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = CardsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Card::try_from(s.parse::<Cards>()?)
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.rank(), self.suit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_layout() {
+        for rank in Rank::ALL {
+            for suit in Suit::ALL {
+                let card = Card::new(rank, suit);
+                assert_eq!(rank.value() & suit.value(), card.value());
+                assert_eq!(Ok(card), Card::try_from(Cards::from(card)));
+                assert_eq!(Some(card), Card::from_index(card.index()));
+                assert_eq!(rank, card.rank());
+                assert_eq!(suit, card.suit());
+            }
+        }
+
+        assert_eq!(Card::new(Rank::Two, Suit::Club).value(), TWO & CLUB);
+        assert_eq!(Card::new(Rank::Ace, Suit::Spade).value(), ACE & SPADE);
+        assert_eq!(None, Card::from_index(52));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let two_clubs = Card::new(Rank::Two, Suit::Club);
+        let two_spades = Card::new(Rank::Two, Suit::Spade);
+        let three_clubs = Card::new(Rank::Three, Suit::Club);
+
+        assert!(two_clubs < two_spades);
+        assert!(two_spades < three_clubs);
+        assert!(Rank::Ace > Rank::King);
+        assert!(Suit::Spade > Suit::Heart);
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        for index in 0..52 {
+            let card = Card::from_index(index).unwrap();
+            assert_eq!(Ok(card), card.to_string().parse());
+        }
+
+        assert_eq!("Th", Card::new(Rank::Ten, Suit::Heart).to_string());
+        assert_eq!(Ok(Rank::Queen), "q".parse());
+        assert_eq!(Ok(Suit::Diamond), "D".parse());
+        assert_eq!(Err(CardsError::InvalidRank('1')), "1".parse::<Rank>());
+        assert_eq!(Err(CardsError::Empty), "".parse::<Rank>());
+        assert_eq!(Err(CardsError::Empty), "".parse::<Suit>());
+        assert_eq!(
+            Err(CardsError::InvalidCards(Cards::from(
+                ACE & SPADE | KING & SPADE
+            ))),
+            "AsKs".parse::<Card>()
+        );
+    }
+
+    #[test]
+    fn test_iter_cards() {
+        let cards: Cards = "As 7d 2c".parse().unwrap();
+        let expected = vec![
+            Card::new(Rank::Ace, Suit::Spade),
+            Card::new(Rank::Seven, Suit::Diamond),
+            Card::new(Rank::Two, Suit::Club),
+        ];
+
+        assert_eq!(expected, cards.iter().collect::<Vec<Card>>());
+    }
+}
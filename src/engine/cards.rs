@@ -1,14 +1,22 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+use std::str::FromStr;
 
-use crate::util::bit_iterator::IntoFromLeftBitIterator;
+use crate::util::bit_iterator::{FormLeftBitIterator, IntoFromLeftBitIterator};
 
+use super::card::{Card, Rank, Suit};
 use super::constants::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CardsError {
     InvalidCards(Cards),
+    InvalidRank(char),
+    InvalidSuit(char),
+    MissingSuit(char),
+    Empty,
+    DuplicateCard(Cards),
 }
 
 impl Display for CardsError {
@@ -53,25 +61,42 @@ impl Cards {
     }
 
     pub fn add_cards(&self, cards: &Cards) -> Cards {
-        Cards::from(self.value() | cards.value())
+        *self | *cards
     }
 
     pub fn remove_cards(&self, cards: &Cards) -> Cards {
-        Cards::from((self.value() | cards.value()) ^ cards.value())
+        *self - *cards
     }
 
     pub fn try_add_cards(&self, cards: &Cards) -> Result<Cards, CardsError> {
-        Cards::new(self.value() | cards.value())
+        Cards::new((*self | *cards).value())
     }
 
     pub fn try_remove_cards(&self, cards: &Cards) -> Result<Cards, CardsError> {
-        Cards::new((self.value() | cards.value()) ^ cards.value())
+        Cards::new((*self - *cards).value())
     }
 
     pub fn has(&self, cards: u64) -> bool {
         self.value() & cards != 0
     }
 
+    /// True if every card in `cards` is also in `self`.
+    pub fn contains_all(&self, cards: &Cards) -> bool {
+        (*cards - *self).value() == 0
+    }
+
+    /// True if `self` and `cards` have no card in common.
+    pub fn is_disjoint(&self, cards: &Cards) -> bool {
+        (*self & *cards).value() == 0
+    }
+
+    /// Iterate over the single cards, from highest to lowest.
+    pub fn iter(&self) -> CardsIterator {
+        CardsIterator {
+            bits: (self.value() & FULL_DECK).iter_from_left(),
+        }
+    }
+
     pub fn get_highest(&self, num: usize) -> Option<Self> {
         if num == 0 || self.card_count() < num as u32 {
             return None;
@@ -203,6 +228,135 @@ impl Cards {
     }
 }
 
+macro_rules! impl_cards_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, |$lhs:ident, $rhs:ident| $body:expr) => {
+        impl $op for Cards {
+            type Output = Cards;
+
+            fn $fn(self, rhs: Cards) -> Cards {
+                let ($lhs, $rhs) = (self.value(), rhs.value());
+                Cards::from($body)
+            }
+        }
+
+        impl $op<u64> for Cards {
+            type Output = Cards;
+
+            fn $fn(self, rhs: u64) -> Cards {
+                self.$fn(Cards::from(rhs))
+            }
+        }
+
+        impl $op_assign for Cards {
+            fn $fn_assign(&mut self, rhs: Cards) {
+                *self = self.$fn(rhs);
+            }
+        }
+
+        impl $op_assign<u64> for Cards {
+            fn $fn_assign(&mut self, rhs: u64) {
+                *self = self.$fn(rhs);
+            }
+        }
+    };
+}
+
+impl_cards_op!(BitOr, bitor, BitOrAssign, bitor_assign, |lhs, rhs| lhs
+    | rhs);
+impl_cards_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |lhs, rhs| lhs
+    & rhs);
+impl_cards_op!(Sub, sub, SubAssign, sub_assign, |lhs, rhs| lhs & !rhs);
+
+impl Not for Cards {
+    type Output = Cards;
+
+    /// All cards of [FULL_DECK] that are not in `self`.
+    fn not(self) -> Cards {
+        Cards::from(FULL_DECK & !self.value())
+    }
+}
+
+pub struct CardsIterator {
+    bits: FormLeftBitIterator,
+}
+
+impl Iterator for CardsIterator {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bit = self.bits.next()?;
+        Card::from_index(bit.trailing_zeros() as u8)
+    }
+}
+
+impl IntoIterator for Cards {
+    type Item = Card;
+    type IntoIter = CardsIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &Cards {
+    type Item = Card;
+    type IntoIter = CardsIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for Cards {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut cards = Cards::default();
+        cards.extend(iter);
+        cards
+    }
+}
+
+impl Extend<Card> for Cards {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        for card in iter {
+            *self |= Cards::from(card);
+        }
+    }
+}
+
+impl FromStr for Cards {
+    type Err = CardsError;
+
+    /// Parses card notation such as `"AsKh"`, `"As Kh"`, `"[As, Kh]"` or `"ah kd"`.
+    ///
+    /// Ranks and suits are matched case-insensitively against [RANK_NAMES] and [SUIT_NAMES].
+    /// Whitespace, commas and square brackets separate cards, an empty string parses to no cards.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cards = 0u64;
+
+        let tokens = s
+            .split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | ','))
+            .filter(|token| !token.is_empty());
+
+        for token in tokens {
+            let mut chars = token.chars();
+            while let Some(rank_char) = chars.next() {
+                let rank = Rank::try_from(rank_char)?;
+                let suit_char = chars.next().ok_or(CardsError::MissingSuit(rank_char))?;
+                let suit = Suit::try_from(suit_char)?;
+
+                let card = Card::new(rank, suit).value();
+                if cards & card != 0 {
+                    return Err(CardsError::DuplicateCard(Cards::from(card)));
+                }
+
+                cards |= card;
+            }
+        }
+
+        Ok(Cards::from(cards))
+    }
+}
+
 impl Debug for Cards {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cards({})", self)
@@ -212,23 +366,8 @@ impl Debug for Cards {
 impl Display for Cards {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let card_string = self
-            .value()
-            .iter_from_left()
-            .filter_map(|card| {
-                let rank_i = RANKS
-                    .iter()
-                    .enumerate()
-                    .find(|(_, rank)| *rank & card != 0)
-                    .map(|(i, _)| i)?;
-
-                let suit_i = SUITS
-                    .iter()
-                    .enumerate()
-                    .find(|(_, suit)| *suit & card != 0)
-                    .map(|(i, _)| i)?;
-
-                Some(format!("{}{}", RANK_NAMES[rank_i], SUIT_NAMES[suit_i]))
-            })
+            .iter()
+            .map(|card| card.to_string())
             .collect::<Vec<String>>()
             .join(", ");
 
@@ -248,6 +387,68 @@ mod tests {
         println!("{}", cards)
     }
 
+    #[test]
+    fn test_from_str() {
+        let expected = Cards::from(ACE & SPADE | KING & HEART);
+
+        assert_eq!(Ok(expected), "AsKh".parse());
+        assert_eq!(Ok(expected), "As Kh".parse());
+        assert_eq!(Ok(expected), "[As, Kh]".parse());
+        assert_eq!(Ok(expected), "kH aS".parse());
+        assert_eq!(Ok(expected), expected.to_string().parse());
+        assert_eq!(
+            Ok(Cards::from(ACE & HEART | KING & DIAMOND)),
+            "ah kd".parse()
+        );
+        assert_eq!(Ok(Cards::default()), "[]".parse());
+    }
+
+    #[test]
+    fn test_from_str_errors() {
+        assert_eq!(Err(CardsError::InvalidRank('X')), "Xs".parse::<Cards>());
+        assert_eq!(Err(CardsError::InvalidSuit('x')), "Ax".parse::<Cards>());
+        assert_eq!(Err(CardsError::MissingSuit('K')), "AsK".parse::<Cards>());
+        assert_eq!(Err(CardsError::MissingSuit('A')), "A s".parse::<Cards>());
+        assert_eq!(
+            Err(CardsError::DuplicateCard(Cards::from(ACE & SPADE))),
+            "As Kh as".parse::<Cards>()
+        );
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a: Cards = "As Kh Qd".parse().unwrap();
+        let b: Cards = "Kh Qd Jc".parse().unwrap();
+
+        assert_eq!(Ok(a | b), "As Kh Qd Jc".parse());
+        assert_eq!(Ok(a & b), "Kh Qd".parse());
+        assert_eq!(Ok(a - b), "As".parse());
+        assert_eq!(a & SPADE, a & Cards::from(SPADE));
+        assert_eq!(52 - 3, (!a).card_count());
+        assert_eq!(Cards::from(FULL_DECK), a | !a);
+
+        let mut c = a;
+        c |= b;
+        c -= a;
+        c &= JACK;
+        assert_eq!(Ok(c), "Jc".parse());
+
+        assert!(a.contains_all(&(a & b)));
+        assert!(!a.contains_all(&b));
+        assert!(a.is_disjoint(&!a));
+        assert!(!a.is_disjoint(&b));
+    }
+
+    #[test]
+    fn test_collect_cards() {
+        let a: Cards = "As Kh Qd".parse().unwrap();
+        assert_eq!(a, a.into_iter().collect());
+
+        let mut b: Cards = "2c".parse().unwrap();
+        b.extend(&a);
+        assert_eq!(Ok(b), "As Kh Qd 2c".parse());
+    }
+
     #[test]
     fn test_straight_flush() {
         let hand = Cards::from(
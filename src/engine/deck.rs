@@ -0,0 +1,265 @@
+use std::fmt::Display;
+
+use crate::util::rng::{Rng, Xoshiro256};
+
+use super::card::Card;
+use super::cards::Cards;
+use super::constants::FULL_DECK;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckError {
+    /// Asked for more cards than the deck holds, with the amount asked for.
+    NotEnoughCards(usize),
+    /// The cards are not in the deck, they were dealt already.
+    NotInDeck(Cards),
+}
+
+impl Display for DeckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+/// Pick `count` cards of `cards` uniformly at random, every subset of that size is equally likely.
+///
+/// Panics when `cards` holds fewer than `count` cards.
+pub fn random_subset(cards: Cards, count: u32, rng: &mut impl Rng) -> Cards {
+    assert!(
+        count <= cards.card_count(),
+        "cannot pick {count} out of {} cards",
+        cards.card_count()
+    );
+
+    let mut remaining = cards.value();
+    let mut picked = 0;
+    for _ in 0..count {
+        let mut skipped = remaining;
+        for _ in 0..rng.below(remaining.count_ones() as u64) {
+            skipped &= skipped - 1;
+        }
+
+        let card = skipped & skipped.wrapping_neg();
+        picked |= card;
+        remaining ^= card;
+    }
+    Cards::from(picked)
+}
+
+/// A deck of cards in a random order, dealt from the top.
+///
+/// The order comes from a seedable [Rng], so the same seed always deals the same cards.
+#[derive(Debug, Clone)]
+pub struct Deck<R: Rng = Xoshiro256> {
+    rng: R,
+    /// The full deck, the cards from `next` on are still to be dealt.
+    order: Vec<Card>,
+    next: usize,
+    dealt: Cards,
+    burned: Cards,
+}
+
+impl Deck<Xoshiro256> {
+    pub fn from_seed(seed: u64) -> Self {
+        Deck::new(Xoshiro256::from_seed(seed))
+    }
+}
+
+impl Default for Deck<Xoshiro256> {
+    fn default() -> Self {
+        Deck::from_seed(0)
+    }
+}
+
+impl<R: Rng> Deck<R> {
+    /// A full, shuffled deck.
+    pub fn new(rng: R) -> Self {
+        let mut deck = Deck {
+            rng,
+            order: vec![],
+            next: 0,
+            dealt: Cards::default(),
+            burned: Cards::default(),
+        };
+        deck.reset();
+        deck
+    }
+
+    /// Put every card back and shuffle.
+    pub fn reset(&mut self) {
+        self.order = Cards::from(FULL_DECK).iter().collect();
+        self.next = 0;
+        self.dealt = Cards::default();
+        self.burned = Cards::default();
+        self.shuffle();
+    }
+
+    /// Shuffle the cards that are still in the deck, the dealt cards stay out.
+    pub fn shuffle(&mut self) {
+        // Fisher-Yates over the undealt part of the order.
+        let undealt = &mut self.order[self.next..];
+        for i in (1..undealt.len()).rev() {
+            let j = self.rng.below(i as u64 + 1) as usize;
+            undealt.swap(i, j);
+        }
+    }
+
+    /// Deal `count` cards from the top.
+    pub fn deal(&mut self, count: usize) -> Result<Cards, DeckError> {
+        if count > self.len() {
+            Err(DeckError::NotEnoughCards(count))?;
+        }
+
+        let cards: Cards = self.order[self.next..self.next + count]
+            .iter()
+            .copied()
+            .collect();
+        self.next += count;
+        self.dealt |= cards;
+        Ok(cards)
+    }
+
+    /// Deal the top card.
+    pub fn deal_card(&mut self) -> Result<Card, DeckError> {
+        let card = *self
+            .order
+            .get(self.next)
+            .ok_or(DeckError::NotEnoughCards(1))?;
+        self.next += 1;
+        self.dealt |= Cards::from(card);
+        Ok(card)
+    }
+
+    /// Deal the top card face down, it counts as dealt and is also kept in [Deck::burned].
+    pub fn burn(&mut self) -> Result<Card, DeckError> {
+        let card = self.deal_card()?;
+        self.burned |= Cards::from(card);
+        Ok(card)
+    }
+
+    /// Take specific cards out of the deck, like cards known to be dealt elsewhere.
+    pub fn remove(&mut self, cards: Cards) -> Result<(), DeckError> {
+        if !self.remaining().contains_all(&cards) {
+            Err(DeckError::NotInDeck(cards - self.remaining()))?;
+        }
+
+        let undealt: Vec<Card> = self.order[self.next..]
+            .iter()
+            .copied()
+            .filter(|card| !cards.has(card.value()))
+            .collect();
+        self.order.truncate(self.next);
+        self.order.extend(cards.iter());
+        self.next = self.order.len();
+        self.order.extend(undealt);
+        self.dealt |= cards;
+        Ok(())
+    }
+
+    /// The cards still in the deck.
+    pub fn remaining(&self) -> Cards {
+        self.order[self.next..].iter().copied().collect()
+    }
+
+    /// Every card dealt since the last [Deck::reset], burned cards included.
+    pub fn dealt(&self) -> Cards {
+        self.dealt
+    }
+
+    pub fn burned(&self) -> Cards {
+        self.burned
+    }
+
+    /// The amount of cards still in the deck.
+    pub fn len(&self) -> usize {
+        self.order.len() - self.next
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_subset() {
+        let mut rng = Xoshiro256::from_seed(1);
+        let cards: Cards = "As Kh Qd Jc Ts".parse().unwrap();
+
+        let mut counts = [0u32; 52];
+        for _ in 0..10000 {
+            let subset = random_subset(cards, 3, &mut rng);
+            assert_eq!(3, subset.card_count());
+            assert!(cards.contains_all(&subset));
+
+            for card in subset {
+                counts[card.index() as usize] += 1;
+            }
+        }
+
+        for card in cards {
+            assert!((5700..6300).contains(&counts[card.index() as usize]));
+        }
+        assert_eq!(Cards::default(), random_subset(cards, 0, &mut rng));
+        assert_eq!(cards, random_subset(cards, 5, &mut rng));
+    }
+
+    #[test]
+    fn test_deal() {
+        let mut deck = Deck::from_seed(5);
+        let hand = deck.deal(2).unwrap();
+        let burned = deck.burn().unwrap();
+        let flop = deck.deal(3).unwrap();
+
+        assert_eq!(2, hand.card_count());
+        assert_eq!(3, flop.card_count());
+        assert_eq!(Cards::from(burned), deck.burned());
+        assert_eq!(hand | flop | Cards::from(burned), deck.dealt());
+        assert_eq!(46, deck.len());
+        assert_eq!(!deck.dealt(), deck.remaining());
+
+        assert_eq!(Err(DeckError::NotEnoughCards(47)), deck.deal(47));
+        assert_eq!(46, deck.deal(46).unwrap().card_count());
+        assert!(deck.is_empty());
+        assert_eq!(Err(DeckError::NotEnoughCards(1)), deck.burn());
+
+        deck.reset();
+        assert_eq!(52, deck.len());
+        assert_eq!(Cards::default(), deck.dealt());
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let mut a = Deck::from_seed(9);
+        let mut b = Deck::from_seed(9);
+        let mut c = Deck::from_seed(10);
+
+        let a: Vec<Card> = (0..10).map(|_| a.deal_card().unwrap()).collect();
+        let b: Vec<Card> = (0..10).map(|_| b.deal_card().unwrap()).collect();
+        let c: Vec<Card> = (0..10).map(|_| c.deal_card().unwrap()).collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut deck = Deck::from_seed(2);
+        let known: Cards = "AsKs".parse().unwrap();
+
+        deck.remove(known).unwrap();
+        assert_eq!(50, deck.len());
+        assert!(deck.remaining().is_disjoint(&known));
+        assert_eq!(
+            Err(DeckError::NotInDeck("As".parse().unwrap())),
+            deck.remove("As Ah".parse().unwrap())
+        );
+
+        deck.shuffle();
+        assert!(deck.deal(50).unwrap().is_disjoint(&known));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::util::combinatorics::choose;
+
+use super::cards::Cards;
+use super::constants::*;
+
+/// Amount of distinct hand strengths, every 5, 6 or 7 card hand evaluates to a value below this.
+pub const HAND_RANK_COUNT: u16 = 7462;
+
+pub const HIGH_CARD: u16 = 0;
+pub const PAIR: u16 = HIGH_CARD + 1277;
+pub const TWO_PAIR: u16 = PAIR + 2860;
+pub const THREE_OF_A_KIND: u16 = TWO_PAIR + 858;
+pub const STRAIGHT: u16 = THREE_OF_A_KIND + 858;
+pub const FLUSH: u16 = STRAIGHT + 10;
+pub const FULL_HOUSE: u16 = FLUSH + 1277;
+pub const FOUR_OF_A_KIND: u16 = FULL_HOUSE + 156;
+pub const STRAIGHT_FLUSH: u16 = FOUR_OF_A_KIND + 156;
+
+const RANK_MASKS: usize = 1 << 13;
+
+/// For every 13 bit rank mask, the number of the highest straight in it.
+/// 0 if there is none, 1 for 5 high (the wheel) up to 10 for ace high.
+static STRAIGHTS: [u16; RANK_MASKS] = build_straights();
+
+/// For every 13 bit rank mask, its index among the masks with the same amount of ranks.
+/// Masks of equal size are ordered by their numeric value, which is also their strength as kickers.
+static COLEX: [u16; RANK_MASKS] = build_colex();
+
+/// For every 13 bit rank mask of exactly five ranks that is not a straight, its strength as a high card hand.
+static HIGH_CARDS: [u16; RANK_MASKS] = build_high_cards();
+
+/// Evaluate the strength of the best five cards within `cards`.
+///
+/// Higher is better and equal hands get equal values. `cards` should hold 5, 6 or 7 cards,
+/// other amounts do not panic but give meaningless results.
+///
+/// The hand is reduced to 13 bit rank masks, which are looked up in small precomputed tables.
+/// With at most 7 cards a flush excludes four of a kind and full house, so it is checked first.
+pub fn evaluate(cards: Cards) -> u16 {
+    let value = cards.value() & FULL_DECK;
+
+    for shift in 0..4 {
+        let suited = (value >> shift) & CLUB;
+        if suited.count_ones() >= 5 {
+            return evaluate_flush(compress(suited));
+        }
+    }
+
+    // Count the cards of every rank within its own nibble.
+    let counts = value - ((value >> 1) & 0x5555555555555);
+    let counts = (counts & 0x3333333333333) + ((counts >> 2) & 0x3333333333333);
+
+    let ones = compress(counts & CLUB);
+    let twos = compress((counts >> 1) & CLUB);
+    let fours = compress((counts >> 2) & CLUB);
+
+    let ranks = ones | twos | fours;
+    let trips = ones & twos;
+    let pairs = twos & !ones;
+
+    if fours != 0 {
+        let quads = highest(fours);
+        let kicker = highest(ranks & !(1 << quads));
+        return FOUR_OF_A_KIND + quads * 12 + skip(kicker, quads);
+    }
+
+    if trips != 0 {
+        let three = highest(trips);
+        let rest = (trips & !(1 << three)) | pairs;
+        if rest != 0 {
+            let two = highest(rest);
+            return FULL_HOUSE + three * 12 + skip(two, three);
+        }
+    }
+
+    let straight = STRAIGHTS[ranks as usize];
+    if straight != 0 {
+        return STRAIGHT + straight - 1;
+    }
+
+    if trips != 0 {
+        let three = highest(trips);
+        let kickers = squeeze(keep_highest(ranks & !(1 << three), 2), three);
+        return THREE_OF_A_KIND + three * 66 + COLEX[kickers as usize];
+    }
+
+    if pairs.count_ones() >= 2 {
+        let two_pairs = keep_highest(pairs, 2);
+        let kicker = highest(ranks & !two_pairs);
+        let below_kicker = (two_pairs & ((1 << kicker) - 1)).count_ones() as u16;
+        return TWO_PAIR + COLEX[two_pairs as usize] * 11 + kicker - below_kicker;
+    }
+
+    if pairs != 0 {
+        let pair = highest(pairs);
+        let kickers = squeeze(keep_highest(ranks & !(1 << pair), 3), pair);
+        return PAIR + pair * 220 + COLEX[kickers as usize];
+    }
+
+    HIGH_CARD + HIGH_CARDS[keep_highest(ranks, 5) as usize]
+}
+
+fn evaluate_flush(ranks: u64) -> u16 {
+    let straight = STRAIGHTS[ranks as usize];
+    if straight != 0 {
+        STRAIGHT_FLUSH + straight - 1
+    } else {
+        FLUSH + HIGH_CARDS[keep_highest(ranks, 5) as usize]
+    }
+}
+
+/// Gather the bits at every fourth position (0, 4, ..., 48) into the lowest 13 bits.
+const fn compress(bits: u64) -> u64 {
+    let bits = (bits | (bits >> 3)) & 0x0303030303030303;
+    let bits = (bits | (bits >> 6)) & 0x000F000F000F000F;
+    let bits = (bits | (bits >> 12)) & 0x000000FF000000FF;
+    (bits | (bits >> 24)) & 0xFFFF
+}
+
+/// The highest set bit of `mask`, or 0 when it is empty, which only happens for hands of less than
+/// five cards.
+fn highest(mask: u64) -> u16 {
+    63u16.saturating_sub(mask.leading_zeros() as u16)
+}
+
+fn keep_highest(mut mask: u64, count: u32) -> u64 {
+    while mask.count_ones() > count {
+        mask &= mask - 1;
+    }
+    mask
+}
+
+/// Index of `rank` among the 12 ranks that are not `skipped`.
+fn skip(rank: u16, skipped: u16) -> u16 {
+    if rank > skipped {
+        rank - 1
+    } else {
+        rank
+    }
+}
+
+/// Remove the (unset) bit `skipped` from `mask`, shifting the higher bits down.
+fn squeeze(mask: u64, skipped: u16) -> u64 {
+    let below = (1 << skipped) - 1;
+    (mask & below) | ((mask >> 1) & !below)
+}
+
+const fn build_straights() -> [u16; RANK_MASKS] {
+    let mut table = [0; RANK_MASKS];
+    let wheel = 0b1000000001111;
+
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        let mut high = 12;
+        while high >= 4 {
+            let window = 0b11111 << (high - 4);
+            if mask & window == window {
+                table[mask] = high as u16 - 2;
+                break;
+            }
+            high -= 1;
+        }
+
+        if table[mask] == 0 && mask & wheel == wheel {
+            table[mask] = 1;
+        }
+
+        mask += 1;
+    }
+
+    table
+}
+
+const fn build_colex() -> [u16; RANK_MASKS] {
+    let mut table = [0; RANK_MASKS];
+
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        let mut index = 0;
+        let mut set = 0;
+        let mut position = 0;
+        while position < 13 {
+            if mask & (1 << position) != 0 {
+                set += 1;
+                index += choose(position, set);
+            }
+            position += 1;
+        }
+
+        table[mask] = index as u16;
+        mask += 1;
+    }
+
+    table
+}
+
+const fn build_high_cards() -> [u16; RANK_MASKS] {
+    let straights = build_straights();
+    let mut table = [0; RANK_MASKS];
+
+    let mut next = 0;
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        if mask.count_ones() == 5 && straights[mask] == 0 {
+            table[mask] = next;
+            next += 1;
+        }
+        mask += 1;
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(cards: &str) -> u16 {
+        evaluate(cards.parse().unwrap())
+    }
+
+    #[test]
+    fn test_category_bounds() {
+        assert_eq!(HIGH_CARD, rank("7c 5d 4h 3s 2c"));
+        assert_eq!(PAIR - 1, rank("Ac Kd Qh Js 9c"));
+        assert_eq!(PAIR, rank("2c 2d 3h 4s 5c"));
+        assert_eq!(TWO_PAIR, rank("3c 3d 2h 2s 4c"));
+        assert_eq!(THREE_OF_A_KIND - 1, rank("Ac Ad Kh Ks Qc"));
+        assert_eq!(STRAIGHT, rank("Ac 2d 3h 4s 5c"));
+        assert_eq!(FLUSH - 1, rank("Ac Kd Qh Js Tc"));
+        assert_eq!(FLUSH, rank("7c 5c 4c 3c 2c"));
+        assert_eq!(FULL_HOUSE, rank("2c 2d 2h 3s 3c"));
+        assert_eq!(STRAIGHT_FLUSH - 1, rank("Ac Ad Ah As Kc"));
+        assert_eq!(STRAIGHT_FLUSH, rank("Ac 2c 3c 4c 5c"));
+        assert_eq!(HAND_RANK_COUNT - 1, rank("Ac Kc Qc Jc Tc"));
+    }
+
+    #[test]
+    fn test_best_five_of_seven() {
+        assert_eq!(rank("9s 8s 7s 6s 5s"), rank("9s 8s 7s 6s 5s Th 2c"));
+        assert_eq!(rank("Ah Ad Ac Ks Kd"), rank("Ah Ad Ac Ks Kd Kc 2c"));
+        assert_eq!(rank("Ah Ad Kc Ks Qd"), rank("Ah Ad Kc Ks Qd Qc 2c"));
+        assert_eq!(rank("6h 5d 4c 3s 2d"), rank("6h 5d 4c 3s 2d Ac 2c"));
+        assert_eq!(rank("Ah Kh 9h 5h 3h"), rank("Ah Kh 9h 5h 3h 2h Qd"));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(rank("Ah Ad Kc Qs Jd") > rank("Kh Kd Ac Qs Jd"));
+        assert!(rank("Ah Ad Kc Qs Jd") > rank("Ah Ad Kc Qs Td"));
+        assert!(rank("Ah Ad 2c 2s 3d") > rank("Kh Kd Qc Qs Ad"));
+        assert!(rank("6h 5d 4c 3s 2d") > rank("Ah 5d 4c 3s 2d"));
+        assert!(rank("2h 2d 2c 2s 3d") > rank("Ah Ad Ac Ks Kd"));
+    }
+
+    #[test]
+    fn test_too_few_cards() {
+        // Meaningless, but within bounds and without overflowing on the missing kickers.
+        for cards in [
+            "",
+            "Ac",
+            "Ac Ad",
+            "Ac Ad Ah",
+            "Ac Ad Ah As",
+            "Ac Ad Kc Kd",
+            "Ac Kd Qh Js",
+        ] {
+            assert!(rank(cards) < HAND_RANK_COUNT, "{}", cards);
+        }
+    }
+}
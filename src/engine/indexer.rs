@@ -0,0 +1,547 @@
+//! Dense indices for cards dealt over several rounds, like 2 hole cards followed by a flop.
+//!
+//! Built on the combinatorial number system: the ranks every suit receives in every round are a
+//! sequence of rank sets, each indexed by its colex rank among the ranks still free in that suit.
+//! The suits are grouped by how many cards they receive per round, that configuration selects a
+//! block of indices and the suit indices are mixed radix digits within it. With suit isomorphism,
+//! suits with the same configuration are interchangeable, so their indices form a multiset
+//! (Kevin Waugh, "A Fast and Optimal Hand Isomorphism Algorithm", 2013).
+
+use std::fmt::Display;
+
+use crate::util::combinatorics::choose;
+
+use super::cards::Cards;
+
+const RANK_COUNT: u32 = 13;
+const SUIT_COUNT: usize = 4;
+const MAX_ROUNDS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexerError {
+    InvalidRounds(Vec<usize>),
+    WrongGroupCount(usize),
+    InvalidGroup(Cards),
+    OverlappingCards(Cards),
+    IndexOutOfRange(u64),
+}
+
+impl Display for IndexerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+/// The colex rank of the ascending, distinct `values`.
+fn rank_colex(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| choose(*value, i as u64 + 1))
+        .sum()
+}
+
+/// The ascending, distinct `k` values below `universe` with colex rank `index`.
+fn unrank_colex(mut index: u64, k: usize, universe: u64) -> Vec<u64> {
+    let mut values = vec![0; k];
+    let mut upper = universe;
+    for i in (1..=k as u64).rev() {
+        // The largest value whose binomial still fits in what is left of the index.
+        let (mut low, mut high) = (i - 1, upper - 1);
+        while low < high {
+            let middle = (low + high).div_ceil(2);
+            if choose(middle, i) <= index {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+
+        index -= choose(low, i);
+        values[i as usize - 1] = low;
+        upper = low;
+    }
+    values
+}
+
+/// The rank of the ascending `values` as a multiset of `values.len()` values below some bound.
+fn rank_multiset(values: &[u64]) -> u64 {
+    let distinct: Vec<u64> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value + i as u64)
+        .collect();
+    rank_colex(&distinct)
+}
+
+fn unrank_multiset(index: u64, k: usize, bound: u64) -> Vec<u64> {
+    unrank_colex(index, k, bound + k as u64 - 1)
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value - i as u64)
+        .collect()
+}
+
+/// The ranks of `cards` in `suit` as a 13 bit mask.
+fn suit_ranks(cards: Cards, suit: usize) -> u16 {
+    let value = cards.value() >> suit;
+    (0..RANK_COUNT).fold(0, |ranks, rank| {
+        ranks | (((value >> (rank * 4)) & 1) as u16) << rank
+    })
+}
+
+fn suit_cards(ranks: u16, suit: usize) -> Cards {
+    let value = (0..RANK_COUNT)
+        .filter(|rank| ranks & (1 << rank) != 0)
+        .fold(0u64, |value, rank| value | 1 << (rank * 4 + suit as u32));
+    Cards::from(value)
+}
+
+/// The amount of cards one suit receives in every round, packed with the first round on top so
+/// packed shapes compare like the sequences.
+type Shape = u32;
+
+fn shape_counts(shape: Shape, rounds: usize) -> impl Iterator<Item = u32> {
+    (0..rounds).map(move |round| (shape >> (4 * (rounds - 1 - round))) & 0xF)
+}
+
+/// The amount of ways one suit can receive the ranks of `shape`.
+fn shape_size(shape: Shape, rounds: usize) -> u64 {
+    let mut free = RANK_COUNT;
+    shape_counts(shape, rounds)
+        .map(|count| {
+            let size = choose(free as u64, count as u64);
+            free -= count;
+            size
+        })
+        .product()
+}
+
+/// Index the rank sets one suit receives per round, each relative to the ranks still free.
+fn rank_suit(rank_sets: &[u16]) -> u64 {
+    let mut used = 0u16;
+    let mut radix = 1;
+    let mut index = 0;
+    for ranks in rank_sets {
+        let positions: Vec<u64> = (0..RANK_COUNT as u16)
+            .filter(|rank| ranks & (1 << rank) != 0)
+            .map(|rank| (rank as u32 - (used & ((1 << rank) - 1)).count_ones()) as u64)
+            .collect();
+
+        index += rank_colex(&positions) * radix;
+        radix *= choose(
+            (RANK_COUNT - used.count_ones()) as u64,
+            ranks.count_ones() as u64,
+        );
+        used |= ranks;
+    }
+    index
+}
+
+fn unrank_suit(mut index: u64, shape: Shape, rounds: usize) -> Vec<u16> {
+    let mut used = 0u16;
+    shape_counts(shape, rounds)
+        .map(|count| {
+            let free: Vec<u16> = (0..RANK_COUNT as u16)
+                .filter(|rank| used & (1 << rank) == 0)
+                .collect();
+            let size = choose(free.len() as u64, count as u64);
+
+            let ranks = unrank_colex(index % size, count as usize, free.len() as u64)
+                .iter()
+                .fold(0, |ranks, position| ranks | 1 << free[*position as usize]);
+            index /= size;
+            used |= ranks;
+            ranks
+        })
+        .collect()
+}
+
+/// One block of indices: the shape of every suit, in the order the suits are indexed.
+#[derive(Debug, Clone)]
+struct Configuration {
+    shapes: [Shape; SUIT_COUNT],
+    offset: u64,
+}
+
+/// All configurations of the first rounds, sorted by shapes.
+#[derive(Debug, Clone)]
+struct RoundTable {
+    rounds: usize,
+    configurations: Vec<Configuration>,
+    size: u64,
+}
+
+/// Dense indices for cards dealt in rounds, see the [module](self) documentation.
+#[derive(Debug, Clone)]
+pub struct HandIndexer {
+    rounds: Vec<usize>,
+    isomorphic: bool,
+    tables: Vec<RoundTable>,
+}
+
+impl HandIndexer {
+    /// An indexer where every combination of cards has its own index.
+    ///
+    /// `rounds` holds the amount of cards dealt per round, `[2, 3, 1, 1]` for hold'em.
+    pub fn new(rounds: &[usize]) -> Result<Self, IndexerError> {
+        Self::build(rounds, false)
+    }
+
+    /// An indexer where combinations that only differ by a permutation of the suits share an
+    /// index, see [super::isomorphism].
+    pub fn isomorphic(rounds: &[usize]) -> Result<Self, IndexerError> {
+        Self::build(rounds, true)
+    }
+
+    fn build(rounds: &[usize], isomorphic: bool) -> Result<Self, IndexerError> {
+        if rounds.is_empty()
+            || rounds.len() > MAX_ROUNDS
+            || rounds.contains(&0)
+            || rounds.iter().sum::<usize>() > RANK_COUNT as usize * SUIT_COUNT
+        {
+            Err(IndexerError::InvalidRounds(rounds.to_vec()))?;
+        }
+
+        let mut indexer = HandIndexer {
+            rounds: rounds.to_vec(),
+            isomorphic,
+            tables: vec![],
+        };
+        indexer.tables = (1..=rounds.len())
+            .map(|count| indexer.build_table(count))
+            .collect();
+
+        Ok(indexer)
+    }
+
+    fn build_table(&self, rounds: usize) -> RoundTable {
+        let mut shapes = vec![];
+        self.collect_shapes(rounds, &mut [0; SUIT_COUNT], 0, &mut shapes);
+        shapes.sort_unstable();
+
+        let mut size = 0;
+        let configurations = shapes
+            .into_iter()
+            .map(|shapes| {
+                let configuration = Configuration {
+                    shapes,
+                    offset: size,
+                };
+                size += self
+                    .groups(&shapes, rounds)
+                    .map(|group| group.1)
+                    .product::<u64>();
+                configuration
+            })
+            .collect();
+
+        RoundTable {
+            rounds,
+            configurations,
+            size,
+        }
+    }
+
+    /// Every way to spread the cards of the first `rounds` rounds over the suits from `suit` on.
+    fn collect_shapes(
+        &self,
+        rounds: usize,
+        shapes: &mut [Shape; SUIT_COUNT],
+        suit: usize,
+        result: &mut Vec<[Shape; SUIT_COUNT]>,
+    ) {
+        if suit == SUIT_COUNT {
+            let complete = (0..rounds).all(|round| {
+                let dealt: u32 = shapes
+                    .iter()
+                    .map(|shape| shape_counts(*shape, rounds).nth(round).unwrap())
+                    .sum();
+                dealt as usize == self.rounds[round]
+            });
+            if complete {
+                result.push(*shapes);
+            }
+            return;
+        }
+
+        let mut counts = vec![0; rounds];
+        loop {
+            let total: u32 = counts.iter().sum();
+            let shape = counts.iter().fold(0, |shape, count| (shape << 4) | count);
+            // Isomorphic suits are indexed from the largest shape down.
+            let ordered = !self.isomorphic || suit == 0 || shape <= shapes[suit - 1];
+            if total <= RANK_COUNT && ordered {
+                shapes[suit] = shape;
+                self.collect_shapes(rounds, shapes, suit + 1, result);
+            }
+
+            // Count up like an odometer, every round up to the cards dealt in it.
+            let Some(round) = (0..rounds)
+                .rev()
+                .find(|round| counts[*round] < self.rounds[*round] as u32)
+            else {
+                break;
+            };
+            counts[round] += 1;
+            counts[round + 1..].fill(0);
+        }
+    }
+
+    /// The groups of interchangeable suits as `(first suit, size, suits)`, in index order.
+    fn groups<'a>(
+        &'a self,
+        shapes: &'a [Shape; SUIT_COUNT],
+        rounds: usize,
+    ) -> impl Iterator<Item = (usize, u64, usize)> + 'a {
+        let mut suit = 0;
+        std::iter::from_fn(move || {
+            if suit == SUIT_COUNT {
+                return None;
+            }
+
+            let first = suit;
+            suit += 1;
+            while self.isomorphic && suit < SUIT_COUNT && shapes[suit] == shapes[first] {
+                suit += 1;
+            }
+
+            let count = suit - first;
+            let size = shape_size(shapes[first], rounds);
+            Some((first, choose(size + count as u64 - 1, count as u64), count))
+        })
+    }
+
+    /// The amount of cards dealt per round.
+    pub fn rounds(&self) -> &[usize] {
+        &self.rounds
+    }
+
+    /// Whether suit isomorphic combinations share an index.
+    pub fn is_isomorphic(&self) -> bool {
+        self.isomorphic
+    }
+
+    /// The amount of indices for all rounds, every index below it is valid.
+    pub fn size(&self) -> u64 {
+        self.tables.last().unwrap().size
+    }
+
+    /// The amount of indices for the cards up to and including `round`.
+    ///
+    /// Panics when `round` is not below the amount of rounds.
+    pub fn round_size(&self, round: usize) -> u64 {
+        self.tables[round].size
+    }
+
+    /// The index of `groups`, the cards dealt in each round.
+    ///
+    /// Fewer groups than rounds index the first rounds only, in [HandIndexer::round_size].
+    pub fn index(&self, groups: &[Cards]) -> Result<u64, IndexerError> {
+        if groups.is_empty() || groups.len() > self.rounds.len() {
+            Err(IndexerError::WrongGroupCount(groups.len()))?;
+        }
+
+        let mut known = Cards::default();
+        for (cards, count) in groups.iter().zip(self.rounds.iter()) {
+            if !cards.is_valid() || cards.card_count() as usize != *count {
+                Err(IndexerError::InvalidGroup(*cards))?;
+            }
+            if !known.is_disjoint(cards) {
+                Err(IndexerError::OverlappingCards(known & *cards))?;
+            }
+            known |= *cards;
+        }
+
+        let table = &self.tables[groups.len() - 1];
+        let mut suits: Vec<(Shape, u64)> = (0..SUIT_COUNT)
+            .map(|suit| {
+                let rank_sets: Vec<u16> = groups
+                    .iter()
+                    .map(|cards| suit_ranks(*cards, suit))
+                    .collect();
+                let shape = rank_sets
+                    .iter()
+                    .fold(0, |shape, ranks| (shape << 4) | ranks.count_ones());
+                (shape, rank_suit(&rank_sets))
+            })
+            .collect();
+        if self.isomorphic {
+            suits.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        let shapes: [Shape; SUIT_COUNT] = std::array::from_fn(|suit| suits[suit].0);
+        let configuration = &table.configurations[table
+            .configurations
+            .binary_search_by_key(&shapes, |configuration| configuration.shapes)
+            .expect("every valid deal has a configuration")];
+
+        let mut index = 0;
+        let mut radix = 1;
+        for (first, size, count) in self.groups(&shapes, table.rounds) {
+            let mut values: Vec<u64> = suits[first..first + count]
+                .iter()
+                .map(|suit| suit.1)
+                .collect();
+            values.sort_unstable();
+
+            index += rank_multiset(&values) * radix;
+            radix *= size;
+        }
+
+        Ok(configuration.offset + index)
+    }
+
+    /// The cards of every round for `index`, the inverse of [HandIndexer::index].
+    ///
+    /// With suit isomorphism this is one representative of the combinations sharing the index.
+    pub fn unindex(&self, index: u64) -> Result<Vec<Cards>, IndexerError> {
+        self.unindex_round(self.rounds.len() - 1, index)
+    }
+
+    /// [HandIndexer::unindex] for the cards up to and including `round`.
+    ///
+    /// Panics when `round` is not below the amount of rounds.
+    pub fn unindex_round(&self, round: usize, index: u64) -> Result<Vec<Cards>, IndexerError> {
+        let table = &self.tables[round];
+        if index >= table.size {
+            Err(IndexerError::IndexOutOfRange(index))?;
+        }
+
+        let position = table
+            .configurations
+            .partition_point(|configuration| configuration.offset <= index)
+            - 1;
+        let configuration = &table.configurations[position];
+
+        let mut groups = vec![Cards::default(); table.rounds];
+        let mut remaining = index - configuration.offset;
+        for (first, size, count) in self.groups(&configuration.shapes, table.rounds) {
+            let shape = configuration.shapes[first];
+            let values = unrank_multiset(remaining % size, count, shape_size(shape, table.rounds));
+            remaining /= size;
+
+            for (suit, value) in (first..first + count).zip(values) {
+                let rank_sets = unrank_suit(value, shape, table.rounds);
+                for (cards, ranks) in groups.iter_mut().zip(rank_sets) {
+                    *cards |= suit_cards(ranks, suit);
+                }
+            }
+        }
+
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::constants::FULL_DECK;
+    use crate::engine::isomorphism::canonicalize;
+    use crate::util::all_bit_combo_iterator::IntoAllBitIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        let plain = HandIndexer::new(&[2, 3, 1, 1]).unwrap();
+        assert_eq!(
+            [1326, 25989600, 1221511200, 56189515200],
+            [0, 1, 2, 3].map(|round| plain.round_size(round))
+        );
+
+        let isomorphic = HandIndexer::isomorphic(&[2, 3, 1, 1]).unwrap();
+        assert_eq!(
+            [169, 1286792, 55190538, 2428287420],
+            [0, 1, 2, 3].map(|round| isomorphic.round_size(round))
+        );
+        assert_eq!(2428287420, isomorphic.size());
+
+        assert_eq!(1755, HandIndexer::isomorphic(&[3]).unwrap().size());
+    }
+
+    #[test]
+    fn test_plain_hands() {
+        let indexer = HandIndexer::new(&[2]).unwrap();
+
+        let mut seen = vec![false; indexer.size() as usize];
+        for hand in FULL_DECK.iter_all_combos(2).map(Cards::from) {
+            let index = indexer.index(&[hand]).unwrap();
+            assert!(!seen[index as usize], "{}", hand);
+            seen[index as usize] = true;
+            assert_eq!(vec![hand], indexer.unindex(index).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_isomorphic_matches_canonicalize() {
+        let indexer = HandIndexer::isomorphic(&[2, 3]).unwrap();
+        let hand: Cards = "AsKs".parse().unwrap();
+
+        for (i, board) in (!hand).value().iter_all_combos(3).enumerate() {
+            if !i.is_multiple_of(97) {
+                continue;
+            }
+
+            let groups = [hand, Cards::from(board)];
+            let index = indexer.index(&groups).unwrap();
+            let representative = indexer.unindex(index).unwrap();
+            assert_eq!(
+                canonicalize(&groups),
+                canonicalize(&representative),
+                "{} {}",
+                groups[0],
+                groups[1]
+            );
+            assert_eq!(index, indexer.index(&representative).unwrap());
+        }
+
+        assert_eq!(
+            indexer.index(&["AsKs".parse().unwrap(), "2s7h9d".parse().unwrap()]),
+            indexer.index(&["AhKh".parse().unwrap(), "2h7c9s".parse().unwrap()])
+        );
+        assert_eq!(
+            indexer.index(&["AsKs".parse().unwrap()]),
+            indexer.index(&["AdKd".parse().unwrap()])
+        );
+    }
+
+    #[test]
+    fn test_isomorphic_round_trip() {
+        let indexer = HandIndexer::isomorphic(&[2, 3]).unwrap();
+
+        for index in 0..indexer.size() {
+            let groups = indexer.unindex(index).unwrap();
+            assert_eq!(index, indexer.index(&groups).unwrap());
+        }
+        for index in 0..indexer.round_size(0) {
+            let groups = indexer.unindex_round(0, index).unwrap();
+            assert_eq!(index, indexer.index(&groups).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let indexer = HandIndexer::new(&[2, 3]).unwrap();
+        let hand: Cards = "AsKs".parse().unwrap();
+
+        assert_eq!(
+            Err(IndexerError::InvalidRounds(vec![2, 0])),
+            HandIndexer::new(&[2, 0]).map(|_| ())
+        );
+        assert_eq!(Err(IndexerError::WrongGroupCount(0)), indexer.index(&[]));
+        assert_eq!(
+            Err(IndexerError::InvalidGroup("2c".parse().unwrap())),
+            indexer.index(&["2c".parse().unwrap(), hand])
+        );
+        assert_eq!(
+            Err(IndexerError::OverlappingCards("As".parse().unwrap())),
+            indexer.index(&[hand, "As 2c 3c".parse().unwrap()])
+        );
+        assert_eq!(
+            Err(IndexerError::IndexOutOfRange(indexer.size())),
+            indexer.unindex(indexer.size())
+        );
+    }
+}
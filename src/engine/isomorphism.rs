@@ -0,0 +1,200 @@
+//! Canonical forms of cards under the 24 permutations of the suits.
+//!
+//! Suits have no order in hold'em, so `AsKs` on `2s7h9d` plays exactly like `AhKh` on `2h7s9d`.
+//! Mapping every hand to one representative of its class lets enumerations, caches and solvers
+//! work on far fewer states, the multiplicity says how many real states each one stands for.
+
+use super::card::Suit;
+use super::cards::Cards;
+use super::constants::CLUB;
+
+/// The amount of ways to permute the four suits.
+pub const SUIT_PERMUTATION_COUNT: usize = 24;
+
+/// Every permutation of the suit indices, `permutation[suit]` is the suit it maps to.
+const PERMUTATIONS: [[u8; 4]; SUIT_PERMUTATION_COUNT] = build_permutations();
+
+const fn build_permutations() -> [[u8; 4]; SUIT_PERMUTATION_COUNT] {
+    let mut permutations = [[0; 4]; SUIT_PERMUTATION_COUNT];
+    let mut count = 0;
+    let mut a = 0;
+    while a < 4 {
+        let mut b = 0;
+        while b < 4 {
+            let mut c = 0;
+            while c < 4 {
+                if a != b && a != c && b != c {
+                    permutations[count] = [a, b, c, 6 - a - b - c];
+                    count += 1;
+                }
+                c += 1;
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    permutations
+}
+
+/// Every permutation of the suits, `permutation[suit.index()]` is the suit `suit` maps to.
+pub fn suit_permutations() -> impl Iterator<Item = [Suit; 4]> {
+    PERMUTATIONS
+        .iter()
+        .map(|permutation| permutation.map(|suit| Suit::ALL[suit as usize]))
+}
+
+/// Move every card of `cards` to the suit `permutation` maps its suit to, keeping its rank.
+pub fn permute_suits(cards: Cards, permutation: [Suit; 4]) -> Cards {
+    permute_indices(cards, &permutation.map(|suit| suit.index()))
+}
+
+fn permute_indices(cards: Cards, permutation: &[u8; 4]) -> Cards {
+    // A suit is every 4th bit, so moving a suit is a shift by the difference of the indices.
+    let value = cards.value();
+    let permuted = (0..4).fold(0, |permuted, suit| {
+        permuted | ((value >> suit) & CLUB) << permutation[suit]
+    });
+    Cards::from(permuted)
+}
+
+/// The representative of a class of suit isomorphic card groups, see [canonicalize].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canonical {
+    groups: Vec<Cards>,
+    multiplicity: u32,
+}
+
+impl Canonical {
+    /// The groups of the representative, in the order they were given.
+    pub fn groups(&self) -> &[Cards] {
+        &self.groups
+    }
+
+    /// The amount of distinct groups, the representative included, that map to this class.
+    pub fn multiplicity(&self) -> u32 {
+        self.multiplicity
+    }
+}
+
+/// Map `groups` to the representative of its class under the 24 suit permutations.
+///
+/// Every group is permuted with the same permutation, so the groups keep their roles: pass
+/// `[hand, board]` to treat the hole cards and the board separately, or `[hand]` for a hand
+/// alone. The representative is the permutation of the smallest group values, compared group
+/// by group, so two inputs are isomorphic exactly when their representatives are equal.
+pub fn canonicalize(groups: &[Cards]) -> Canonical {
+    let mut images: Vec<Vec<u64>> = PERMUTATIONS
+        .iter()
+        .map(|permutation| {
+            groups
+                .iter()
+                .map(|cards| permute_indices(*cards, permutation).value())
+                .collect()
+        })
+        .collect();
+    images.sort_unstable();
+    images.dedup();
+
+    Canonical {
+        groups: images[0].iter().copied().map(Cards::from).collect(),
+        multiplicity: images.len() as u32,
+    }
+}
+
+/// [canonicalize] of hole cards, dealt before the board.
+pub fn canonical_hand(hand: Cards) -> Canonical {
+    canonicalize(&[hand])
+}
+
+/// [canonicalize] of hole cards together with a board, keeping the two apart.
+pub fn canonical_hand_board(hand: Cards, board: Cards) -> Canonical {
+    canonicalize(&[hand, board])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::constants::FULL_DECK;
+    use crate::util::all_bit_combo_iterator::IntoAllBitIterator;
+
+    use super::*;
+
+    fn cards(s: &str) -> Cards {
+        s.parse().unwrap()
+    }
+
+    /// The distinct classes of all `size` card groups from the deck, with their multiplicities.
+    fn classes(size: usize) -> Vec<Canonical> {
+        let mut classes: Vec<Canonical> = FULL_DECK
+            .iter_all_combos(size)
+            .map(|group| canonicalize(&[Cards::from(group)]))
+            .collect();
+        classes.sort_unstable_by_key(|canonical| canonical.groups()[0].value());
+        classes.dedup();
+        classes
+    }
+
+    #[test]
+    fn test_permutations() {
+        let mut permutations: Vec<[Suit; 4]> = suit_permutations().collect();
+        permutations.sort_unstable();
+        permutations.dedup();
+        assert_eq!(SUIT_PERMUTATION_COUNT, permutations.len());
+
+        let swap = [Suit::Spade, Suit::Diamond, Suit::Heart, Suit::Club];
+        assert_eq!(cards("AsKc 2h"), permute_suits(cards("AcKs 2h"), swap));
+    }
+
+    #[test]
+    fn test_preflop_classes() {
+        let classes = classes(2);
+
+        assert_eq!(169, classes.len());
+        assert_eq!(
+            1326,
+            classes.iter().map(Canonical::multiplicity).sum::<u32>()
+        );
+
+        let pairs = classes
+            .iter()
+            .filter(|canonical| canonical.multiplicity() == 6);
+        let suited = classes
+            .iter()
+            .filter(|canonical| canonical.multiplicity() == 4);
+        let offsuit = classes
+            .iter()
+            .filter(|canonical| canonical.multiplicity() == 12);
+        assert_eq!(
+            (13, 78, 78),
+            (pairs.count(), suited.count(), offsuit.count())
+        );
+    }
+
+    #[test]
+    fn test_flop_classes() {
+        let classes = classes(3);
+
+        assert_eq!(1755, classes.len());
+        assert_eq!(
+            22100,
+            classes.iter().map(Canonical::multiplicity).sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn test_hand_board() {
+        let a = canonical_hand_board(cards("AsKs"), cards("2s7h9d"));
+        let b = canonical_hand_board(cards("AhKh"), cards("2h7s9c"));
+        let c = canonical_hand_board(cards("AhKh"), cards("2s7h9d"));
+
+        assert_eq!(a, b);
+        assert_ne!(a.groups(), c.groups());
+        assert_eq!(24, a.multiplicity());
+
+        // The board may not leak into the hand, even when that gives a smaller value.
+        let split = canonical_hand_board(cards("2c"), cards("3c"));
+        let joined = canonical_hand(cards("2c3c"));
+        assert_eq!(4, split.multiplicity());
+        assert_eq!(2, split.groups().len());
+        assert_eq!(joined.groups()[0], split.groups()[0] | split.groups()[1]);
+    }
+}
@@ -0,0 +1,497 @@
+use std::time::{Duration, Instant};
+
+use crate::util::rng::{Rng, Xoshiro256};
+
+use super::cards::Cards;
+use super::deck::random_subset;
+use super::evaluator::evaluate;
+use super::out_runner::{in_parallel, remaining_deck, validate_table, RunoutError};
+use super::range::Range;
+
+/// How long to sample in [sample_runout], [sample_multiway] and [sample_ranges].
+///
+/// Sampling stops at whichever limit is hit first. The same seed and trial count always give the
+/// same result, a time budget does not since the amount of trials depends on the machine.
+#[derive(Debug, Clone)]
+pub struct SampleConfig {
+    pub seed: u64,
+    pub max_trials: u64,
+    pub time_budget: Option<Duration>,
+    /// Stop once the standard error of every equity is at most this.
+    pub target_std_error: Option<f64>,
+}
+
+impl Default for SampleConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_trials: 100_000,
+            time_budget: None,
+            target_std_error: None,
+        }
+    }
+}
+
+/// Trials between checking the time budget and the target precision.
+const BATCH_SIZE: u64 = 1000;
+
+/// Consecutive conflicting range deals before concluding the ranges cannot be dealt together.
+const MAX_REJECTIONS: u32 = 10_000;
+
+/// Sampled results of one player.
+///
+/// `equity` is the fraction of the pot won with a k-way tie worth 1/k, its standard error
+/// is computed from the variance of the pot share per trial. The win, tie and loss rates are
+/// binomial proportions with their own standard errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Estimate {
+    trials: u64,
+    wins: u64,
+    ties: u64,
+    pot_share: f64,
+    pot_share_squared: f64,
+}
+
+impl Estimate {
+    pub fn trials(&self) -> u64 {
+        self.trials
+    }
+
+    pub fn win(&self) -> f64 {
+        self.wins as f64 / self.trials.max(1) as f64
+    }
+
+    pub fn tie(&self) -> f64 {
+        self.ties as f64 / self.trials.max(1) as f64
+    }
+
+    pub fn loss(&self) -> f64 {
+        (self.trials - self.wins - self.ties) as f64 / self.trials.max(1) as f64
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.pot_share / self.trials.max(1) as f64
+    }
+
+    pub fn std_error(&self) -> f64 {
+        if self.trials < 2 {
+            return f64::INFINITY;
+        }
+
+        let trials = self.trials as f64;
+        let mean = self.pot_share / trials;
+        let variance = (self.pot_share_squared / trials - mean * mean).max(0.0);
+        (variance / (trials - 1.0)).sqrt()
+    }
+
+    /// The 95% confidence interval of the equity.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.std_error();
+        (self.equity() - margin, self.equity() + margin)
+    }
+
+    pub fn win_std_error(&self) -> f64 {
+        self.proportion_std_error(self.win())
+    }
+
+    pub fn tie_std_error(&self) -> f64 {
+        self.proportion_std_error(self.tie())
+    }
+
+    pub fn loss_std_error(&self) -> f64 {
+        self.proportion_std_error(self.loss())
+    }
+
+    fn proportion_std_error(&self, proportion: f64) -> f64 {
+        if self.trials < 2 {
+            return f64::INFINITY;
+        }
+
+        (proportion * (1.0 - proportion) / (self.trials - 1) as f64).sqrt()
+    }
+
+    pub fn add(&mut self, other: Self) {
+        self.trials += other.trials;
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.pot_share += other.pot_share;
+        self.pot_share_squared += other.pot_share_squared;
+    }
+
+    fn record(&mut self, won: bool, winners: usize) {
+        self.trials += 1;
+        if !won {
+            return;
+        }
+
+        if winners == 1 {
+            self.wins += 1;
+        } else {
+            self.ties += 1;
+        }
+
+        let share = 1.0 / winners as f64;
+        self.pot_share += share;
+        self.pot_share_squared += share * share;
+    }
+}
+
+/// Run trials until `config` says to stop. `trial` deals the hands and returns the full board.
+fn simulate<F>(
+    players: usize,
+    config: &SampleConfig,
+    mut trial: F,
+) -> Result<Vec<Estimate>, RunoutError>
+where
+    F: FnMut(&mut Xoshiro256, &mut Vec<Cards>) -> Result<Cards, RunoutError>,
+{
+    let start = Instant::now();
+    let mut rng = Xoshiro256::from_seed(config.seed);
+    let mut estimates = vec![Estimate::default(); players];
+    let mut hands = Vec::with_capacity(players);
+    let mut ranks = vec![0; players];
+
+    let mut trials = 0;
+    while trials < config.max_trials {
+        hands.clear();
+        let board = trial(&mut rng, &mut hands)?;
+
+        for (rank, hand) in ranks.iter_mut().zip(hands.iter()) {
+            *rank = evaluate(*hand | board);
+        }
+        let best = ranks.iter().copied().max().unwrap_or_default();
+        let winners = ranks.iter().filter(|rank| **rank == best).count();
+
+        for (estimate, rank) in estimates.iter_mut().zip(ranks.iter()) {
+            estimate.record(*rank == best, winners);
+        }
+
+        trials += 1;
+        if trials % BATCH_SIZE != 0 {
+            continue;
+        }
+
+        if config
+            .time_budget
+            .is_some_and(|budget| start.elapsed() >= budget)
+        {
+            break;
+        }
+
+        if let Some(target) = config.target_std_error {
+            if estimates
+                .iter()
+                .all(|estimate| estimate.std_error() <= target)
+            {
+                break;
+            }
+        }
+    }
+
+    Ok(estimates)
+}
+
+/// Sample the runouts of [runout](super::out_runner::runout): `player` against one random
+/// opponent hand, both taken from `deck` along with the rest of the board.
+pub fn sample_runout(
+    player: Cards,
+    table: Cards,
+    deck: Cards,
+    config: &SampleConfig,
+) -> Result<Estimate, RunoutError> {
+    if player.card_count() != 2 {
+        Err(RunoutError::InvalidHand(player))?;
+    }
+    validate_table(table, Cards::default())?;
+
+    let missing = 5 - table.card_count();
+    if deck.card_count() < missing + 2 {
+        Err(RunoutError::CardCountTooLow(table, deck))?;
+    }
+
+    let estimates = simulate(2, config, |rng, hands| {
+        let opponent = random_subset(deck, 2, rng);
+        let board = random_subset(deck - opponent, missing, rng);
+
+        hands.push(player);
+        hands.push(opponent);
+        Ok(table | board)
+    })?;
+
+    Ok(estimates[0])
+}
+
+/// Sample the runouts of [runout_multiway](super::out_runner::runout_multiway).
+pub fn sample_multiway(
+    known_hands: &[Cards],
+    table: Cards,
+    dead: Cards,
+    config: &SampleConfig,
+) -> Result<Vec<Estimate>, RunoutError> {
+    let deck = remaining_deck(known_hands, table, dead)?;
+    let missing = 5 - table.card_count();
+
+    simulate(known_hands.len(), config, |rng, hands| {
+        hands.extend_from_slice(known_hands);
+        Ok(table | random_subset(deck, missing, rng))
+    })
+}
+
+/// Sample the runouts of [runout_ranges](super::out_runner::runout_ranges), dealing every range
+/// a combo by weight and redealing when the combos share cards.
+pub fn sample_ranges(
+    ranges: &[Range],
+    table: Cards,
+    dead: Cards,
+    config: &SampleConfig,
+) -> Result<Vec<Estimate>, RunoutError> {
+    if ranges.is_empty() {
+        Err(RunoutError::NoHands)?;
+    }
+    validate_table(table, dead)?;
+
+    let known = table | dead;
+    let missing = 5 - table.card_count();
+
+    // Cumulative weights of the combos that do not conflict with the known cards.
+    let combos: Vec<Vec<(Cards, f64)>> = ranges
+        .iter()
+        .map(|range| {
+            let mut total = 0.0;
+            range
+                .combos()
+                .filter(|(combo, _)| combo.is_disjoint(&known))
+                .map(|(combo, weight)| {
+                    total += weight;
+                    (combo, total)
+                })
+                .collect()
+        })
+        .collect();
+
+    if combos.iter().any(|combos| combos.is_empty()) {
+        Err(RunoutError::NoValidMatchups)?;
+    }
+
+    simulate(ranges.len(), config, |rng, hands| {
+        for _ in 0..MAX_REJECTIONS {
+            hands.clear();
+            let mut used = known;
+
+            for combos in combos.iter() {
+                let total = combos[combos.len() - 1].1;
+                let pick = rng.next_f64() * total;
+                let index = combos.partition_point(|(_, cumulative)| *cumulative <= pick);
+                let (combo, _) = combos[index.min(combos.len() - 1)];
+
+                if !combo.is_disjoint(&used) {
+                    break;
+                }
+                used |= combo;
+                hands.push(combo);
+            }
+
+            if hands.len() == combos.len() {
+                return Ok(table | random_subset(!used, missing, rng));
+            }
+        }
+
+        Err(RunoutError::NoValidMatchups)
+    })
+}
+
+/// [sample_ranges] split over `threads` worker threads, 0 uses
+/// [default_threads](super::out_runner::default_threads).
+///
+/// Worker `i` samples its share of the trials with seed `config.seed + i` and the estimates are
+/// merged with [Estimate::add], so the result depends on the seed and the amount of threads. Every
+/// worker aims for a standard error `sqrt(threads)` times the target, which the merged estimate
+/// then roughly meets.
+pub fn sample_ranges_parallel(
+    ranges: &[Range],
+    table: Cards,
+    dead: Cards,
+    config: &SampleConfig,
+    threads: usize,
+) -> Result<Vec<Estimate>, RunoutError> {
+    let results = in_parallel(threads, |worker, threads| {
+        let share = config.max_trials / threads as u64;
+        let worker_config = SampleConfig {
+            seed: config.seed.wrapping_add(worker as u64),
+            max_trials: share + ((worker as u64) < config.max_trials % threads as u64) as u64,
+            target_std_error: config
+                .target_std_error
+                .map(|target| target * (threads as f64).sqrt()),
+            ..config.clone()
+        };
+        sample_ranges(ranges, table, dead, &worker_config)
+    });
+
+    let mut estimates = vec![Estimate::default(); ranges.len()];
+    for worker_estimates in results {
+        for (estimate, worker_estimate) in estimates.iter_mut().zip(worker_estimates?) {
+            estimate.add(worker_estimate);
+        }
+    }
+    Ok(estimates)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::out_runner::{runout_multiway, runout_ranges};
+
+    use super::*;
+
+    fn cards(cards: &str) -> Cards {
+        cards.parse().unwrap()
+    }
+
+    #[test]
+    fn test_sample_multiway_matches_exact() {
+        let hands = [cards("AsKs"), cards("QhQd"), cards("JcTc")];
+        let table = cards("7h8h2d");
+        let config = SampleConfig {
+            seed: 3,
+            max_trials: 200_000,
+            ..SampleConfig::default()
+        };
+
+        let exact = runout_multiway(&hands, table, Cards::default()).unwrap();
+        let sampled = sample_multiway(&hands, table, Cards::default(), &config).unwrap();
+
+        for (exact, sampled) in exact.iter().zip(sampled.iter()) {
+            assert_eq!(200_000, sampled.trials());
+            assert!((exact.equity() - sampled.equity()).abs() < 4.0 * sampled.std_error());
+            assert!((exact.win() - sampled.win()).abs() < 4.0 * sampled.win_std_error());
+            // No ties at all give a standard error of 0.
+            assert!((exact.tie() - sampled.tie()).abs() <= 4.0 * sampled.tie_std_error());
+            assert!((exact.loss() - sampled.loss()).abs() < 4.0 * sampled.loss_std_error());
+            assert!((sampled.win() + sampled.tie() + sampled.loss() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_proportion_std_errors() {
+        let mut estimate = Estimate::default();
+        assert_eq!(f64::INFINITY, estimate.win_std_error());
+
+        // 3 wins, 1 split pot and 1 loss out of 5 trials.
+        for (won, winners) in [(true, 1), (true, 1), (true, 1), (true, 2), (false, 1)] {
+            estimate.record(won, winners);
+        }
+        assert!((estimate.win_std_error() - (0.6 * 0.4 / 4.0f64).sqrt()).abs() < 1e-12);
+        assert!((estimate.tie_std_error() - (0.2 * 0.8 / 4.0f64).sqrt()).abs() < 1e-12);
+        assert!((estimate.loss_std_error() - (0.2 * 0.8 / 4.0f64).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sample_is_reproducible() {
+        let config = SampleConfig {
+            seed: 11,
+            max_trials: 5000,
+            ..SampleConfig::default()
+        };
+        let player = cards("AhKh");
+        let table = Cards::default();
+        let deck = !player;
+
+        let first = sample_runout(player, table, deck, &config).unwrap();
+        let second = sample_runout(player, table, deck, &config).unwrap();
+        let other_seed =
+            sample_runout(player, table, deck, &SampleConfig { seed: 12, ..config }).unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_seed);
+    }
+
+    #[test]
+    fn test_sample_stops_at_precision() {
+        let config = SampleConfig {
+            max_trials: u64::MAX,
+            target_std_error: Some(0.01),
+            ..SampleConfig::default()
+        };
+
+        let estimate =
+            sample_runout(cards("AhAd"), Cards::default(), !cards("AhAd"), &config).unwrap();
+
+        assert!(estimate.std_error() <= 0.01);
+        assert!(estimate.trials() < 10_000);
+        let (low, high) = estimate.confidence_interval();
+        assert!(low < 0.85 && 0.85 < high, "{} {}", low, high);
+    }
+
+    #[test]
+    fn test_sample_stops_at_time_budget() {
+        let config = SampleConfig {
+            max_trials: u64::MAX,
+            time_budget: Some(Duration::from_millis(20)),
+            ..SampleConfig::default()
+        };
+
+        let hands = [cards("AhAd"), cards("KsKc")];
+        let estimates =
+            sample_multiway(&hands, Cards::default(), Cards::default(), &config).unwrap();
+
+        assert!(estimates[0].trials() > 0);
+        assert_eq!(estimates[0].trials(), estimates[1].trials());
+    }
+
+    #[test]
+    fn test_sample_ranges_matches_exact() {
+        let ranges: Vec<Range> = vec!["AsKs".parse().unwrap(), "AA, KK:0.5, QJs".parse().unwrap()];
+        let table = cards("Qs Js 2h 3d");
+        let config = SampleConfig {
+            seed: 5,
+            max_trials: 100_000,
+            ..SampleConfig::default()
+        };
+
+        let exact = runout_ranges(&ranges, table, Cards::default()).unwrap();
+        let sampled = sample_ranges(&ranges, table, Cards::default(), &config).unwrap();
+
+        for (exact, sampled) in exact.iter().zip(sampled.iter()) {
+            let exact = exact.equity().equity();
+            assert!((exact - sampled.equity()).abs() < 4.0 * sampled.std_error());
+        }
+    }
+
+    #[test]
+    fn test_sample_ranges_no_matchups() {
+        let ranges: Vec<Range> = vec!["AsAh".parse().unwrap(), "AsAh".parse().unwrap()];
+
+        assert!(matches!(
+            sample_ranges(
+                &ranges,
+                Cards::default(),
+                Cards::default(),
+                &SampleConfig::default()
+            ),
+            Err(RunoutError::NoValidMatchups)
+        ));
+    }
+
+    #[test]
+    fn test_sample_ranges_parallel() {
+        let ranges: Vec<Range> = vec!["AsKs".parse().unwrap(), "AA, KK:0.5, QJs".parse().unwrap()];
+        let table = cards("Qs Js 2h 3d");
+        let config = SampleConfig {
+            seed: 5,
+            max_trials: 40_001,
+            ..SampleConfig::default()
+        };
+
+        let exact = runout_ranges(&ranges, table, Cards::default()).unwrap();
+        let sampled = sample_ranges_parallel(&ranges, table, Cards::default(), &config, 4).unwrap();
+        assert_eq!(
+            sampled,
+            sample_ranges_parallel(&ranges, table, Cards::default(), &config, 4).unwrap()
+        );
+
+        for (exact, sampled) in exact.iter().zip(sampled.iter()) {
+            assert_eq!(40_001, sampled.trials());
+            let exact = exact.equity().equity();
+            assert!((exact - sampled.equity()).abs() < 4.0 * sampled.std_error());
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use std::fmt::Display;
+
 use crate::util::all_bit_combo_iterator::IntoAllBitIterator;
 
 use super::cards::Cards;
-use super::outcome::Outcome;
+use super::evaluator::evaluate;
 use super::outcome::OutcomeError;
+use super::range::Range;
 
 #[derive(Debug)]
 pub enum RunoutError {
@@ -10,8 +13,19 @@ pub enum RunoutError {
     InvalidTable(Cards),
     CardCountTooLow(Cards, Cards),
     InvalidOutcome(Cards),
+    NoHands,
+    OverlappingCards(Cards),
+    NoValidMatchups,
+}
+
+impl Display for RunoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
 }
 
+impl std::error::Error for RunoutError {}
+
 impl From<OutcomeError> for RunoutError {
     fn from(value: OutcomeError) -> Self {
         match value {
@@ -22,76 +36,594 @@ impl From<OutcomeError> for RunoutError {
     }
 }
 
-#[derive(Debug)]
+/// Exact counts of the showdowns of one player over a runout.
+///
+/// `splits[k]` counts the showdowns the player won together with `k` others, so `k` of 0 is an
+/// outright win and any other `k` is a tie worth `1 / (k + 1)` of the pot.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Chance {
-    win: f32,
-    tie: f32,
-    loss: f32,
+    runouts: u64,
+    splits: Vec<u64>,
 }
 
-impl Default for Chance {
-    fn default() -> Self {
-        Self {
-            win: 0.0,
-            tie: 0.0,
-            loss: 0.0,
+impl Chance {
+    fn record_win(&mut self, others: usize) {
+        if self.splits.len() <= others {
+            self.splits.resize(others + 1, 0);
+        }
+        self.splits[others] += 1;
+        self.runouts += 1;
+    }
+
+    fn record_loss(&mut self) {
+        self.runouts += 1;
+    }
+
+    /// The amount of showdowns counted.
+    pub fn runouts(&self) -> u64 {
+        self.runouts
+    }
+
+    /// The showdowns won together with exactly `others` other players.
+    pub fn split(&self, others: usize) -> u64 {
+        self.splits.get(others).copied().unwrap_or_default()
+    }
+
+    pub fn win(&self) -> u64 {
+        self.split(0)
+    }
+
+    pub fn tie(&self) -> u64 {
+        self.splits.iter().skip(1).sum()
+    }
+
+    pub fn loss(&self) -> u64 {
+        self.runouts - self.win() - self.tie()
+    }
+
+    /// The share of the pot won over all showdowns, with a k-way tie worth 1/k.
+    pub fn equity(&self) -> f64 {
+        self.normalize().equity
+    }
+
+    /// The counts as fractions of [Chance::runouts].
+    ///
+    /// Counts below 2^53 convert to `f64` without rounding, so only the division rounds.
+    pub fn normalize(&self) -> Equity {
+        if self.runouts == 0 {
+            return Equity::default();
+        }
+
+        let total = self.runouts as f64;
+        Equity {
+            win: self.win() as f64 / total,
+            tie: self.tie() as f64 / total,
+            loss: self.loss() as f64 / total,
+            equity: self
+                .splits
+                .iter()
+                .enumerate()
+                .map(|(k, count)| *count as f64 / (k + 1) as f64)
+                .sum::<f64>()
+                / total,
+        }
+    }
+
+    pub fn add(&mut self, other: &Self) {
+        if self.splits.len() < other.splits.len() {
+            self.splits.resize(other.splits.len(), 0);
+        }
+        for (count, other_count) in self.splits.iter_mut().zip(other.splits.iter()) {
+            *count += other_count;
         }
+        self.runouts += other.runouts;
     }
 }
 
-impl Chance {
-    pub fn normalize(self) -> Chance {
-        let total = self.win + self.tie + self.loss;
-        if total != 0.0 {
-            Chance {
-                win: self.win / total,
-                tie: self.tie / total,
-                loss: self.loss / total,
-            }
+/// The normalized result of one player, see [Chance::normalize].
+///
+/// `win`, `tie` and `loss` are the fractions of runouts the player won alone, shared or lost,
+/// `equity` is the fraction of the pot the player wins, with a k-way tie worth 1/k.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Equity {
+    win: f64,
+    tie: f64,
+    loss: f64,
+    equity: f64,
+}
+
+impl Equity {
+    pub fn win(&self) -> f64 {
+        self.win
+    }
+
+    pub fn tie(&self) -> f64 {
+        self.tie
+    }
+
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.equity
+    }
+}
+
+/// Showdown all `hands` on `board` and count the result in the [Chance] of every hand.
+///
+/// `ranks` is scratch space of one rank per hand, reused between boards.
+fn showdown(chances: &mut [Chance], ranks: &mut [u16], hands: &[Cards], board: Cards) {
+    for (rank, hand) in ranks.iter_mut().zip(hands) {
+        *rank = evaluate(*hand | board);
+    }
+    let best = ranks.iter().copied().max().unwrap_or_default();
+    let winners = ranks.iter().filter(|rank| **rank == best).count();
+
+    for (chance, rank) in chances.iter_mut().zip(ranks.iter()) {
+        if *rank == best {
+            chance.record_win(winners - 1);
         } else {
-            Chance::default()
+            chance.record_loss();
+        }
+    }
+}
+
+fn normalize_all(chances: &[Chance]) -> Vec<Equity> {
+    chances.iter().map(Chance::normalize).collect()
+}
+
+pub(crate) fn validate_table(table: Cards, dead: Cards) -> Result<(), RunoutError> {
+    if table.card_count() > 5 {
+        Err(RunoutError::InvalidTable(table))?;
+    }
+
+    if !table.is_disjoint(&dead) {
+        Err(RunoutError::OverlappingCards(table & dead))?;
+    }
+
+    Ok(())
+}
+
+/// Validate the known cards of a multiway runout and return the cards that can still be dealt.
+pub(crate) fn remaining_deck(
+    hands: &[Cards],
+    table: Cards,
+    dead: Cards,
+) -> Result<Cards, RunoutError> {
+    if hands.is_empty() {
+        Err(RunoutError::NoHands)?;
+    }
+
+    validate_table(table, dead)?;
+
+    let mut known = table | dead;
+    for cards in hands {
+        if !known.is_disjoint(cards) {
+            Err(RunoutError::OverlappingCards(known & *cards))?;
         }
+        known |= *cards;
+    }
+
+    if let Some(hand) = hands.iter().find(|hand| hand.card_count() != 2) {
+        Err(RunoutError::InvalidHand(*hand))?;
     }
 
-    pub fn add(&mut self, other: Self) {
-        self.win += other.win;
-        self.tie += other.tie;
-        self.loss += other.loss;
+    Ok(!known)
+}
+
+/// Enumerate every board that completes `table` and showdown all `hands` on it.
+///
+/// Cards in `hands`, `table` and `dead` are never dealt. Returns one [Equity] per hand, in order.
+pub fn runout_multiway(
+    hands: &[Cards],
+    table: Cards,
+    dead: Cards,
+) -> Result<Vec<Equity>, RunoutError> {
+    let deck = remaining_deck(hands, table, dead)?;
+    Ok(normalize_all(&enumerate_boards(hands, table, deck)))
+}
+
+/// Every board that completes `table` with cards from `deck`.
+fn boards(table: Cards, deck: Cards) -> impl Iterator<Item = Cards> {
+    deck.value()
+        .iter_all_combos(5 - table.card_count() as usize)
+        .map(move |new_table_cards| table | new_table_cards)
+}
+
+fn enumerate_boards(hands: &[Cards], table: Cards, deck: Cards) -> Vec<Chance> {
+    tally_boards(hands, boards(table, deck))
+}
+
+fn tally_boards(hands: &[Cards], boards: impl Iterator<Item = Cards>) -> Vec<Chance> {
+    let mut chances = vec![Chance::default(); hands.len()];
+    let mut ranks = vec![0; hands.len()];
+    for board in boards {
+        showdown(&mut chances, &mut ranks, hands, board);
+    }
+    chances
+}
+
+/// The amount of worker threads to use when asked for 0, one per available core.
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// Run `work(worker, threads)` on `threads` scoped worker threads and collect the results in order.
+///
+/// A `threads` of 0 uses [default_threads].
+pub(crate) fn in_parallel<T, F>(threads: usize, work: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize, usize) -> T + Sync,
+{
+    let threads = if threads == 0 {
+        default_threads()
+    } else {
+        threads
+    };
+
+    std::thread::scope(|scope| {
+        let work = &work;
+        let handles: Vec<_> = (0..threads)
+            .map(|worker| scope.spawn(move || work(worker, threads)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("runout worker panicked"))
+            .collect()
+    })
+}
+
+/// [runout_multiway] with the boards split over `threads` worker threads, 0 uses [default_threads].
+///
+/// Every worker takes every `threads`-th board, the result is exactly that of [runout_multiway].
+pub fn runout_multiway_parallel(
+    hands: &[Cards],
+    table: Cards,
+    dead: Cards,
+    threads: usize,
+) -> Result<Vec<Equity>, RunoutError> {
+    let deck = remaining_deck(hands, table, dead)?;
+
+    let tallies = in_parallel(threads, |worker, threads| {
+        tally_boards(hands, boards(table, deck).skip(worker).step_by(threads))
+    });
+
+    let mut chances = vec![Chance::default(); hands.len()];
+    for worker_chances in tallies.iter() {
+        for (chance, worker_chance) in chances.iter_mut().zip(worker_chances) {
+            chance.add(worker_chance);
+        }
     }
+
+    Ok(normalize_all(&chances))
+}
+
+/// The equity of one combo of a range in [runout_ranges].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComboEquity {
+    combo: Cards,
+    weight: f64,
+    equity: Equity,
 }
 
+impl ComboEquity {
+    pub fn combo(&self) -> Cards {
+        self.combo
+    }
+
+    /// The chance this combo is the one held, after removing the cards of the other players.
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn equity(&self) -> Equity {
+        self.equity
+    }
+}
+
+/// The equity of one range in [runout_ranges], overall and per combo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeEquity {
+    equity: Equity,
+    combos: Vec<ComboEquity>,
+}
+
+impl RangeEquity {
+    pub fn equity(&self) -> Equity {
+        self.equity
+    }
+
+    /// Every combo that could be held, in the order of [Range::combos].
+    pub fn combos(&self) -> &[ComboEquity] {
+        &self.combos
+    }
+}
+
+/// Weighted sum of equities, divided by the total weight when finished.
+#[derive(Debug, Clone, Copy, Default)]
+struct EquitySum {
+    weight: f64,
+    win: f64,
+    tie: f64,
+    loss: f64,
+    equity: f64,
+}
+
+impl EquitySum {
+    fn add(&mut self, weight: f64, equity: &Equity) {
+        self.weight += weight;
+        self.win += weight * equity.win;
+        self.tie += weight * equity.tie;
+        self.loss += weight * equity.loss;
+        self.equity += weight * equity.equity;
+    }
+
+    fn finish(&self) -> Equity {
+        if self.weight == 0.0 {
+            return Equity::default();
+        }
+
+        Equity {
+            win: self.win / self.weight,
+            tie: self.tie / self.weight,
+            loss: self.loss / self.weight,
+            equity: self.equity / self.weight,
+        }
+    }
+}
+
+/// Enumerate every matchup of combos from `ranges` and every board that completes `table`.
+///
+/// A matchup is weighted by the product of its combo weights, matchups that share cards with each
+/// other, `table` or `dead` are skipped. Returns one [RangeEquity] per range, in order.
+pub fn runout_ranges(
+    ranges: &[Range],
+    table: Cards,
+    dead: Cards,
+) -> Result<Vec<RangeEquity>, RunoutError> {
+    let combos = range_combos(ranges, table, dead)?;
+    let mut sums = MatchupSums::new(&combos);
+
+    for_each_matchup(&combos, table | dead, |picks, hands, used| {
+        let equities = normalize_all(&enumerate_boards(hands, table, !used));
+        sums.add(&combos, picks, &equities);
+    });
+
+    finish_ranges(&combos, &sums)
+}
+
+/// Matchups whose boards are enumerated by the workers at once in [runout_ranges_parallel].
+const MATCHUP_BATCH: usize = 1024;
+
+/// [runout_ranges] with the matchups split over `threads` worker threads, 0 uses
+/// [default_threads].
+///
+/// The matchups are handed out in batches, every worker takes every `threads`-th matchup of a
+/// batch and the equities are summed in the order of [runout_ranges], so the result is exactly
+/// that of [runout_ranges].
+pub fn runout_ranges_parallel(
+    ranges: &[Range],
+    table: Cards,
+    dead: Cards,
+    threads: usize,
+) -> Result<Vec<RangeEquity>, RunoutError> {
+    let combos = range_combos(ranges, table, dead)?;
+    let mut sums = MatchupSums::new(&combos);
+    let mut batch: Vec<(Vec<usize>, Vec<Cards>, Cards)> = Vec::with_capacity(MATCHUP_BATCH);
+
+    let mut sum_batch = |batch: &mut Vec<(Vec<usize>, Vec<Cards>, Cards)>| {
+        let worker_equities = in_parallel(threads, |worker, threads| {
+            batch
+                .iter()
+                .skip(worker)
+                .step_by(threads)
+                .map(|(_, hands, used)| normalize_all(&enumerate_boards(hands, table, !*used)))
+                .collect::<Vec<_>>()
+        });
+
+        // Matchup `i` of the batch was done by worker `i % threads` as its `i / threads`-th.
+        let threads = worker_equities.len();
+        for (i, (picks, _, _)) in batch.iter().enumerate() {
+            sums.add(&combos, picks, &worker_equities[i % threads][i / threads]);
+        }
+        batch.clear();
+    };
+
+    for_each_matchup(&combos, table | dead, |picks, hands, used| {
+        batch.push((picks.to_vec(), hands.to_vec(), used));
+        if batch.len() == MATCHUP_BATCH {
+            sum_batch(&mut batch);
+        }
+    });
+    if !batch.is_empty() {
+        sum_batch(&mut batch);
+    }
+
+    finish_ranges(&combos, &sums)
+}
+
+/// The combos of every range that do not share cards with `table` or `dead`.
+fn range_combos(
+    ranges: &[Range],
+    table: Cards,
+    dead: Cards,
+) -> Result<Vec<Vec<(Cards, f64)>>, RunoutError> {
+    if ranges.is_empty() {
+        Err(RunoutError::NoHands)?;
+    }
+
+    validate_table(table, dead)?;
+
+    let known = table | dead;
+    Ok(ranges
+        .iter()
+        .map(|range| {
+            range
+                .combos()
+                .filter(|(combo, _)| combo.is_disjoint(&known))
+                .collect()
+        })
+        .collect())
+}
+
+/// The weighted equities of every range, overall and per combo.
+#[derive(Debug, Clone)]
+struct MatchupSums {
+    overall: Vec<EquitySum>,
+    per_combo: Vec<Vec<EquitySum>>,
+}
+
+impl MatchupSums {
+    fn new(combos: &[Vec<(Cards, f64)>]) -> Self {
+        MatchupSums {
+            overall: vec![EquitySum::default(); combos.len()],
+            per_combo: combos
+                .iter()
+                .map(|combos| vec![EquitySum::default(); combos.len()])
+                .collect(),
+        }
+    }
+
+    /// Add the equities of the matchup of combo `picks[i]` of every player `i`.
+    fn add(&mut self, combos: &[Vec<(Cards, f64)>], picks: &[usize], equities: &[Equity]) {
+        let weight: f64 = (0..combos.len()).map(|i| combos[i][picks[i]].1).product();
+        for (i, equity) in equities.iter().enumerate() {
+            self.overall[i].add(weight, equity);
+            self.per_combo[i][picks[i]].add(weight, equity);
+        }
+    }
+}
+
+/// Call `visit` for every matchup of combos that share no cards with each other or `known`, with
+/// the index of the combo of every player, their hands and all cards that can not be dealt.
+fn for_each_matchup(
+    combos: &[Vec<(Cards, f64)>],
+    known: Cards,
+    mut visit: impl FnMut(&[usize], &[Cards], Cards),
+) {
+    let players = combos.len();
+    let mut picks = vec![0; players];
+    let mut hands = vec![Cards::default(); players];
+    let mut player = 0;
+    let mut used = known;
+
+    // Depth first walk over the combos of every player, `picks[player]` is the next combo to try.
+    loop {
+        if picks[player] == combos[player].len() {
+            if player == 0 {
+                break;
+            }
+            picks[player] = 0;
+            player -= 1;
+            used -= hands[player];
+            picks[player] += 1;
+            continue;
+        }
+
+        let (combo, _) = combos[player][picks[player]];
+        if !combo.is_disjoint(&used) {
+            picks[player] += 1;
+            continue;
+        }
+
+        hands[player] = combo;
+        if player + 1 < players {
+            used |= combo;
+            player += 1;
+            continue;
+        }
+
+        visit(&picks, &hands, used | combo);
+        picks[player] += 1;
+    }
+}
+
+fn finish_ranges(
+    combos: &[Vec<(Cards, f64)>],
+    sums: &MatchupSums,
+) -> Result<Vec<RangeEquity>, RunoutError> {
+    if sums.overall[0].weight == 0.0 {
+        Err(RunoutError::NoValidMatchups)?;
+    }
+
+    Ok((0..combos.len())
+        .map(|i| RangeEquity {
+            equity: sums.overall[i].finish(),
+            combos: combos[i]
+                .iter()
+                .zip(sums.per_combo[i].iter())
+                .filter(|(_, sum)| sum.weight > 0.0)
+                .map(|((combo, _), sum)| ComboEquity {
+                    combo: *combo,
+                    weight: sum.weight / sums.overall[i].weight,
+                    equity: sum.finish(),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Count the showdowns of `player` against every opponent hand from `deck` on every board that
+/// completes `table` with cards from `deck`.
 pub fn runout(player: Cards, table: Cards, deck: Cards) -> Result<Chance, RunoutError> {
     if player.card_count() != 2 {
         Err(RunoutError::InvalidHand(player))?;
     }
 
+    Ok(runout_boards(player, deck, boards(table, deck)))
+}
+
+/// [runout] with the boards split over `threads` worker threads, 0 uses [default_threads].
+///
+/// Every worker takes every `threads`-th board and the counts are merged with [Chance::add],
+/// so the result is exactly that of [runout].
+pub fn runout_parallel(
+    player: Cards,
+    table: Cards,
+    deck: Cards,
+    threads: usize,
+) -> Result<Chance, RunoutError> {
+    if player.card_count() != 2 {
+        Err(RunoutError::InvalidHand(player))?;
+    }
+
+    let chances = in_parallel(threads, |worker, threads| {
+        runout_boards(
+            player,
+            deck,
+            boards(table, deck).skip(worker).step_by(threads),
+        )
+    });
+
     let mut chance = Chance::default();
-    for new_table_cards in deck
-        .value()
-        .iter_all_combos(5 - table.card_count() as usize)
-    {
-        let new_table = table.add_cards(&Cards::from(new_table_cards));
-
-        let player_outcome: Outcome = player
-            .add_cards(&new_table)
-            .try_into()
-            .map_err(RunoutError::from)?;
-
-        for opponent_cards in deck.remove_cards(&new_table).value().iter_all_combos(2) {
-            let opponent_outcome: Outcome = Cards::from(opponent_cards)
-                .add_cards(&new_table)
-                .try_into()
-                .map_err(RunoutError::from)?;
-
-            match player_outcome.cmp(&opponent_outcome) {
-                std::cmp::Ordering::Greater => chance.win += 1f32,
-                std::cmp::Ordering::Equal => chance.tie += 1f32,
-                std::cmp::Ordering::Less => chance.loss += 1f32,
+    for worker_chance in chances {
+        chance.add(&worker_chance);
+    }
+
+    Ok(chance)
+}
+
+/// Count the outcomes of `player` against every opponent hand from `deck` on each of `boards`.
+fn runout_boards(player: Cards, deck: Cards, boards: impl Iterator<Item = Cards>) -> Chance {
+    let mut chance = Chance::default();
+    for new_table in boards {
+        let player_rank = evaluate(player | new_table);
+
+        for opponent_cards in (deck - new_table).value().iter_all_combos(2) {
+            let opponent_rank = evaluate(new_table | opponent_cards);
+
+            match player_rank.cmp(&opponent_rank) {
+                std::cmp::Ordering::Greater => chance.record_win(0),
+                std::cmp::Ordering::Equal => chance.record_win(1),
+                std::cmp::Ordering::Less => chance.record_loss(),
             }
         }
     }
 
-    Ok(chance.normalize())
+    chance
 }
 
 #[cfg(test)]
@@ -99,6 +631,8 @@ mod tests {
     use crate::engine::cards::Cards;
     use crate::engine::constants::*;
 
+    use super::*;
+
     #[test]
     fn test_runout_table() {
         let hand = Cards::from(ACE & CLUB | ACE & DIAMOND);
@@ -118,9 +652,237 @@ mod tests {
                 | TEN & HEART,
         );
 
-        // let chance = runout_table(hand, table, deck).unwrap();
+        // Quad aces with no nines left for a straight flush win every showdown: 66 boards from
+        // the 12 cards, each against the 45 opponent hands from the other 10.
+        let chance = runout(hand, table, deck).unwrap();
+        assert_eq!(66 * 45, chance.runouts());
+        assert_eq!(chance.runouts(), chance.win());
+    }
+
+    #[test]
+    fn test_runout_multiway_split() {
+        let hands = ["AsAh".parse().unwrap(), "KsKh".parse().unwrap()];
+        let table: Cards = "2c 3d 4h 5s".parse().unwrap();
+
+        let equities = runout_multiway(&hands, table, Cards::default()).unwrap();
 
-        // println!("chance {:?}", chance)
+        // A six or one of the two remaining aces gives both the same straight.
+        assert_eq!(38.0 / 44.0, equities[0].win());
+        assert_eq!(6.0 / 44.0, equities[0].tie());
+        assert_eq!(41.0 / 44.0, equities[0].equity());
+        assert_eq!(0.0, equities[1].win());
+        assert_eq!(6.0 / 44.0, equities[1].tie());
+        assert_eq!(3.0 / 44.0, equities[1].equity());
+    }
+
+    #[test]
+    fn test_runout_multiway_three_way() {
+        let hands = [
+            "AsKs".parse().unwrap(),
+            "QhQd".parse().unwrap(),
+            "JcTc".parse().unwrap(),
+        ];
+        let table: Cards = "7h8h2d".parse().unwrap();
+        let dead: Cards = "2c".parse().unwrap();
+
+        let equities = runout_multiway(&hands, table, dead).unwrap();
+
+        let total: f64 = equities.iter().map(|equity| equity.equity()).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(equities[1].equity() > equities[0].equity());
+        for equity in equities {
+            assert!(equity.win() + equity.tie() <= 1.0);
+            assert!(equity.equity() >= equity.win());
+        }
+    }
+
+    #[test]
+    fn test_runout_multiway_errors() {
+        let hand: Cards = "AsKs".parse().unwrap();
+
+        assert!(matches!(
+            runout_multiway(&[], Cards::default(), Cards::default()),
+            Err(RunoutError::NoHands)
+        ));
+        assert!(matches!(
+            runout_multiway(
+                &[hand, "AsQs".parse().unwrap()],
+                Cards::default(),
+                Cards::default()
+            ),
+            Err(RunoutError::OverlappingCards(_))
+        ));
+        assert!(matches!(
+            runout_multiway(&[hand], "Ah".parse().unwrap(), "Ah".parse().unwrap()),
+            Err(RunoutError::OverlappingCards(_))
+        ));
+        assert!(matches!(
+            runout_multiway(&["As".parse().unwrap()], Cards::default(), Cards::default()),
+            Err(RunoutError::InvalidHand(_))
+        ));
+    }
+
+    #[test]
+    fn test_runout_ranges_single_combos() {
+        let hands: [Cards; 2] = ["AsAh".parse().unwrap(), "KsKh".parse().unwrap()];
+        let ranges: Vec<Range> = hands
+            .iter()
+            .map(|hand| Range::from_combo(*hand).unwrap())
+            .collect();
+        let table: Cards = "2c 3d 4h 5s".parse().unwrap();
+
+        let range_equities = runout_ranges(&ranges, table, Cards::default()).unwrap();
+        let equities = runout_multiway(&hands, table, Cards::default()).unwrap();
+
+        for (range_equity, equity) in range_equities.iter().zip(equities) {
+            assert_eq!(equity, range_equity.equity());
+            assert_eq!(1, range_equity.combos().len());
+            assert_eq!(1.0, range_equity.combos()[0].weight());
+        }
+    }
+
+    #[test]
+    fn test_runout_ranges_card_removal() {
+        let ranges: Vec<Range> = vec!["AsKs".parse().unwrap(), "AA, KK:0.5".parse().unwrap()];
+        let table: Cards = "Qs Js 2h 3d".parse().unwrap();
+
+        let equities = runout_ranges(&ranges, table, Cards::default()).unwrap();
+
+        // AsKs blocks three combos of aces and three of kings.
+        let villain = &equities[1];
+        assert_eq!(6, villain.combos().len());
+        assert!(villain.combos().iter().all(|combo| combo
+            .combo()
+            .is_disjoint(&ranges[0].combos().next().unwrap().0)));
+
+        let aces_weight: f64 = villain
+            .combos()
+            .iter()
+            .filter(|combo| combo.combo().has(ACE))
+            .map(|combo| combo.weight())
+            .sum();
+        assert!((aces_weight - 2.0 / 3.0).abs() < 1e-9);
+
+        let expected: f64 = villain
+            .combos()
+            .iter()
+            .map(|combo| combo.weight() * combo.equity().equity())
+            .sum();
+        assert!((expected - villain.equity().equity()).abs() < 1e-9);
+        assert!((equities[0].equity().equity() + villain.equity().equity() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_runout_ranges_no_matchups() {
+        let ranges: Vec<Range> = vec!["AsAh".parse().unwrap(), "AsAh".parse().unwrap()];
+
+        assert!(matches!(
+            runout_ranges(&ranges, Cards::default(), Cards::default()),
+            Err(RunoutError::NoValidMatchups)
+        ));
+    }
+
+    #[test]
+    fn test_runout_counts() {
+        let player: Cards = "AsAh".parse().unwrap();
+        let table: Cards = "2c 3d 4h 5s".parse().unwrap();
+        let deck = !(player | table);
+
+        let chance = runout(player, table, deck).unwrap();
+
+        // 46 rivers, each against every pair of the 45 cards left.
+        assert_eq!(46 * 990, chance.runouts());
+        assert_eq!(
+            chance.runouts(),
+            chance.win() + chance.tie() + chance.loss()
+        );
+        assert_eq!(chance.tie(), chance.split(1));
+        assert_eq!(0, chance.split(2));
+
+        let equity = chance.normalize();
+        assert_eq!(chance.win() as f64 / chance.runouts() as f64, equity.win());
+        assert_eq!(
+            chance.loss() as f64 / chance.runouts() as f64,
+            equity.loss()
+        );
+        assert!((equity.win() + equity.tie() / 2.0 - equity.equity()).abs() < 1e-12);
+        assert_eq!(Equity::default(), Chance::default().normalize());
+    }
+
+    #[test]
+    fn test_runout_complete_table() {
+        let player: Cards = "AsAh".parse().unwrap();
+        let table: Cards = "Ad 7c 2s 9h Kd".parse().unwrap();
+
+        let chance = runout(player, table, !(player | table)).unwrap();
+
+        // Only the opponent hands are left to deal, and a third ace is never beaten.
+        assert_eq!(990, chance.runouts());
+        assert_eq!(990, chance.win());
+
+        let equities = runout_multiway(&[player, "KsKh".parse().unwrap()], table, Cards::default());
+        assert_eq!(1.0, equities.unwrap()[0].equity());
+    }
+
+    #[test]
+    fn test_chance_add() {
+        let mut three_way = Chance::default();
+        three_way.record_win(2);
+        three_way.record_loss();
+
+        let mut chance = Chance::default();
+        chance.record_win(0);
+        chance.add(&three_way);
+
+        assert_eq!(3, chance.runouts());
+        assert_eq!((1, 1, 1), (chance.win(), chance.tie(), chance.loss()));
+        assert_eq!(1, chance.split(2));
+        assert!((chance.equity() - (1.0 + 1.0 / 3.0) / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_runout_parallel_matches_serial() {
+        let player: Cards = "AsKs".parse().unwrap();
+        let table: Cards = "7h8h2d".parse().unwrap();
+        let deck = !(player | table);
+
+        let serial = runout(player, table, deck).unwrap();
+        for threads in [1, 3, 8] {
+            let parallel = runout_parallel(player, table, deck, threads).unwrap();
+            assert_eq!(serial, parallel);
+        }
+    }
+
+    #[test]
+    fn test_runout_ranges_parallel_matches_serial() {
+        // Enough matchups for several batches.
+        let ranges: Vec<Range> = vec![
+            "TT+, AQ+".parse().unwrap(),
+            "22+, A2s+:0.5".parse().unwrap(),
+        ];
+        let table: Cards = "Qs Js 2h 7c".parse().unwrap();
+
+        let serial = runout_ranges(&ranges, table, Cards::default()).unwrap();
+        for threads in [0, 1, 3, 8] {
+            let parallel = runout_ranges_parallel(&ranges, table, Cards::default(), threads);
+            assert_eq!(serial, parallel.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_runout_multiway_parallel_matches_serial() {
+        let hands = [
+            "AsKs".parse().unwrap(),
+            "QhQd".parse().unwrap(),
+            "JcTc".parse().unwrap(),
+        ];
+        let table: Cards = "7h8h".parse().unwrap();
+
+        let serial = runout_multiway(&hands, table, Cards::default()).unwrap();
+        for threads in [0, 2, 5] {
+            let parallel = runout_multiway_parallel(&hands, table, Cards::default(), threads);
+            assert_eq!(serial, parallel.unwrap());
+        }
     }
 
     #[test]
@@ -128,4 +890,3 @@ mod tests {
         println!("I am testing!");
     }
 }
-
@@ -1,18 +1,18 @@
 use std::cmp::Ordering;
-use std::error::Error;
 use std::fmt::Display;
 
 use super::cards::Cards;
 use super::constants::*;
+use super::evaluator::*;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutcomeError {
     CardCountTooLow(Cards),
     HighestCardNotFound(Cards),
     KindNotFound(Cards),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
     StraightFlush(Cards),
     FourOfAKind(Cards),
@@ -28,87 +28,100 @@ pub enum Outcome {
 impl TryFrom<Cards> for Outcome {
     type Error = OutcomeError;
 
+    /// Decode the [evaluate] rank of `cards` into its category and best five cards.
     fn try_from(cards: Cards) -> Result<Self, Self::Error> {
         if cards.value().count_ones() < 5 {
             return Err(OutcomeError::CardCountTooLow(cards));
         }
 
-        let flush = cards.get_flush();
-        let straight = cards.get_straight();
+        let rank = evaluate(cards);
 
-        if let (Some(flush), Some(straight)) = (flush, straight) {
-            if flush == straight {
-                return Ok(Outcome::StraightFlush(flush));
-            }
+        if rank >= STRAIGHT_FLUSH {
+            let straight_flush = SUITS
+                .iter()
+                .map(|suit| cards & *suit)
+                .find(|suited| suited.card_count() >= 5)
+                .and_then(|suited| suited.get_straight())
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
+
+            return Ok(Outcome::StraightFlush(straight_flush));
         }
 
         let kinds = cards.get_kinds();
 
-        if let Some(quads) = kinds.iter().find(|cards| cards.card_count() == 4) {
-            let cards = cards
-                .remove_cards(quads)
+        if rank >= FOUR_OF_A_KIND {
+            let quads = kinds
+                .iter()
+                .find(|cards| cards.card_count() == 4)
+                .ok_or(OutcomeError::KindNotFound(cards))?;
+
+            let cards = (cards - *quads)
                 .get_highest(1)
-                .ok_or(OutcomeError::HighestCardNotFound(cards))?
-                .add_cards(quads);
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
 
-            return Ok(Outcome::FourOfAKind(cards));
+            return Ok(Outcome::FourOfAKind(cards | *quads));
         }
 
-        if let Some(trips) = kinds.iter().find(|cards| cards.card_count() == 3) {
-            if let Some(pair) = kinds
+        if rank >= FULL_HOUSE {
+            let trips = kinds
+                .iter()
+                .find(|cards| cards.card_count() == 3)
+                .ok_or(OutcomeError::KindNotFound(cards))?;
+
+            let pair = kinds
                 .iter()
                 .find(|cards| cards != &trips && cards.card_count() >= 2)
-            {
-                let pair = pair
-                    .get_highest(2) // May be a pair of more than 2 cards
-                    .ok_or(OutcomeError::HighestCardNotFound(cards))?;
-                let cards = trips.add_cards(&pair);
-
-                return Ok(Outcome::FullHouse(cards));
-            }
+                .and_then(|pair| pair.get_highest(2)) // May be a pair of more than 2 cards
+                .ok_or(OutcomeError::KindNotFound(cards))?;
+
+            return Ok(Outcome::FullHouse(*trips | pair));
         }
 
-        if let Some(flush) = flush {
+        if rank >= FLUSH {
+            let flush = cards
+                .get_flush()
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
+
             return Ok(Outcome::Flush(flush));
         }
 
-        if let Some(straight) = straight {
+        if rank >= STRAIGHT {
+            let straight = cards
+                .get_straight()
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
+
             return Ok(Outcome::Straight(straight));
         }
 
-        if let Some(trips) = kinds.iter().find(|cards| cards.card_count() == 3) {
-            let cards = cards
-                .remove_cards(trips)
+        if rank >= THREE_OF_A_KIND {
+            let trips = kinds.first().ok_or(OutcomeError::KindNotFound(cards))?;
+
+            let cards = (cards - *trips)
                 .get_highest(2)
-                .ok_or(OutcomeError::HighestCardNotFound(cards))?
-                .add_cards(trips);
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
 
-            return Ok(Outcome::ThreeOfAKind(cards));
+            return Ok(Outcome::ThreeOfAKind(cards | *trips));
         }
 
-        if kinds.len() >= 2 {
-            let pair1 = kinds.get(0).ok_or(OutcomeError::KindNotFound(cards))?;
+        if rank >= TWO_PAIR {
+            let pair1 = kinds.first().ok_or(OutcomeError::KindNotFound(cards))?;
             let pair2 = kinds.get(1).ok_or(OutcomeError::KindNotFound(cards))?;
 
-            let cards = cards
-                .remove_cards(pair1)
-                .remove_cards(pair2)
+            let cards = (cards - *pair1 - *pair2)
                 .get_highest(1)
-                .ok_or(OutcomeError::HighestCardNotFound(cards))?
-                .add_cards(pair1)
-                .add_cards(pair2);
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
 
-            return Ok(Outcome::TwoPair(cards));
+            return Ok(Outcome::TwoPair(cards | *pair1 | *pair2));
         }
 
-        if let Some(pair) = kinds.first() {
-            let cards = cards
-                .remove_cards(pair)
+        if rank >= PAIR {
+            let pair = kinds.first().ok_or(OutcomeError::KindNotFound(cards))?;
+
+            let cards = (cards - *pair)
                 .get_highest(3)
-                .ok_or(OutcomeError::HighestCardNotFound(cards))?
-                .add_cards(pair);
+                .ok_or(OutcomeError::HighestCardNotFound(cards))?;
 
-            return Ok(Outcome::Pair(cards));
+            return Ok(Outcome::Pair(cards | *pair));
         }
 
         let high_card = cards
@@ -119,179 +132,71 @@ impl TryFrom<Cards> for Outcome {
     }
 }
 
+impl Outcome {
+    /// The best five cards that make up this outcome.
+    pub fn cards(&self) -> Cards {
+        match self {
+            Outcome::StraightFlush(cards)
+            | Outcome::FourOfAKind(cards)
+            | Outcome::FullHouse(cards)
+            | Outcome::Flush(cards)
+            | Outcome::Straight(cards)
+            | Outcome::ThreeOfAKind(cards)
+            | Outcome::TwoPair(cards)
+            | Outcome::Pair(cards)
+            | Outcome::HighCard(cards) => *cards,
+        }
+    }
+
+    /// The [evaluate] rank of the five cards of this outcome.
+    pub fn rank(&self) -> u16 {
+        evaluate(self.cards())
+    }
+
+    fn category(&self) -> u8 {
+        match self {
+            Outcome::StraightFlush(_) => 8,
+            Outcome::FourOfAKind(_) => 7,
+            Outcome::FullHouse(_) => 6,
+            Outcome::Flush(_) => 5,
+            Outcome::Straight(_) => 4,
+            Outcome::ThreeOfAKind(_) => 3,
+            Outcome::TwoPair(_) => 2,
+            Outcome::Pair(_) => 1,
+            Outcome::HighCard(_) => 0,
+        }
+    }
+}
+
 impl PartialOrd for Outcome {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match (self, other) {
-            (Outcome::StraightFlush(self_cards), Outcome::StraightFlush(other_cards)) => {
-                let mut self_cards = *self_cards;
-                if self_cards.has(ACE) && self_cards.has(FIVE) {
-                    self_cards = self_cards.remove_cards(&Cards::from(self_cards.value() & ACE));
-                }
-
-                let mut other_cards = *other_cards;
-                if other_cards.has(ACE) && other_cards.has(FIVE) {
-                    other_cards = other_cards.remove_cards(&Cards::from(other_cards.value() & ACE));
-                }
-
-                Some(self_cards.compare_rank(&other_cards))
-            }
-            (Outcome::StraightFlush(_), _) => Some(Ordering::Greater),
-            (_, Outcome::StraightFlush(_)) => Some(Ordering::Less),
-            (Outcome::FourOfAKind(self_cards), Outcome::FourOfAKind(other_cards)) => {
-                if self_cards == other_cards {
-                    return Some(Ordering::Equal);
-                }
-
-                let self_kinds = self_cards.get_kinds();
-                let other_kinds = other_cards.get_kinds();
-
-                let self_quads = self_kinds.iter().find(|cards| cards.card_count() == 4)?;
-                let other_quads = other_kinds.iter().find(|cards| cards.card_count() == 4)?;
-
-                let compare_quads = self_quads.compare_rank(other_quads);
-                let Ordering::Equal = compare_quads else {
-                    return Some(compare_quads);
-                };
-
-                let self_rest = self_cards.remove_cards(self_quads);
-                let other_rest = other_cards.remove_cards(other_quads);
-
-                Some(self_rest.compare_rank(&other_rest))
-            }
-            (Outcome::FourOfAKind(_), _) => Some(Ordering::Greater),
-            (_, Outcome::FourOfAKind(_)) => Some(Ordering::Less),
-            (Outcome::FullHouse(self_cards), Outcome::FullHouse(other_cards)) => {
-                if self_cards == other_cards {
-                    return Some(Ordering::Equal);
-                }
-
-                let self_kinds = self_cards.get_kinds();
-                let other_kinds = other_cards.get_kinds();
-
-                let self_trips = self_kinds.iter().find(|cards| cards.card_count() == 3)?;
-                let other_trips = other_kinds.iter().find(|cards| cards.card_count() == 3)?;
-
-                let trips_compare = self_trips.compare_rank(other_trips);
-                let Ordering::Equal = trips_compare else {
-                    return Some(trips_compare);
-                };
-
-                let self_pair = self_kinds.iter().find(|cards| cards.card_count() == 2)?;
-                let other_pair = other_kinds.iter().find(|cards| cards.card_count() == 2)?;
-
-                Some(self_pair.compare_rank(other_pair))
-            }
-            (Outcome::FullHouse(_), _) => Some(Ordering::Greater),
-            (_, Outcome::FullHouse(_)) => Some(Ordering::Less),
-            (Outcome::Flush(self_cards), Outcome::Flush(other_cards)) => {
-                Some(self_cards.compare_rank(other_cards))
-            }
-            (Outcome::Flush(_), _) => Some(Ordering::Greater),
-            (_, Outcome::Flush(_)) => Some(Ordering::Less),
-            (Outcome::Straight(self_cards), Outcome::Straight(other_cards)) => {
-                let mut self_cards = *self_cards;
-                if self_cards.has(ACE) && self_cards.has(FIVE) {
-                    self_cards = self_cards.remove_cards(&Cards::from(self_cards.value() & ACE));
-                }
-
-                let mut other_cards = *other_cards;
-                if other_cards.has(ACE) && other_cards.has(FIVE) {
-                    other_cards = other_cards.remove_cards(&Cards::from(other_cards.value() & ACE));
-                }
-
-                Some(self_cards.compare_rank(&other_cards))
-            }
-            (Outcome::Straight(_), _) => Some(Ordering::Greater),
-            (_, Outcome::Straight(_)) => Some(Ordering::Less),
-            (Outcome::ThreeOfAKind(self_cards), Outcome::ThreeOfAKind(other_cards)) => {
-                if self_cards == other_cards {
-                    return Some(Ordering::Equal);
-                }
-
-                let self_kinds = self_cards.get_kinds();
-                let other_kinds = other_cards.get_kinds();
-
-                let self_three = self_kinds.iter().find(|cards| cards.card_count() == 3)?;
-                let other_three = other_kinds.iter().find(|cards| cards.card_count() == 3)?;
-
-                let compare_three = self_three.compare_rank(other_three);
-                let Ordering::Equal = compare_three else {
-                    return Some(compare_three);
-                };
-
-                let self_rest = self_cards.remove_cards(self_three);
-                let other_rest = other_cards.remove_cards(other_three);
-
-                Some(self_rest.compare_rank(&other_rest))
-            }
-            (Outcome::ThreeOfAKind(_), _) => Some(Ordering::Greater),
-            (_, Outcome::ThreeOfAKind(_)) => Some(Ordering::Less),
-            (Outcome::TwoPair(self_cards), Outcome::TwoPair(other_cards)) => {
-                if self_cards == other_cards {
-                    return Some(Ordering::Equal);
-                }
-
-                let self_kinds = self_cards.get_kinds();
-                let other_kinds = other_cards.get_kinds();
-
-                let self_pair1 = self_kinds.first()?;
-                let other_pair1 = other_kinds.first()?;
-
-                let compare_pair1 = self_pair1.compare_rank(other_pair1);
-                let Ordering::Equal = compare_pair1 else {
-                    return Some(compare_pair1);
-                };
-
-                let self_pair2 = self_kinds.get(1)?;
-                let other_pair2 = other_kinds.get(1)?;
-
-                let compare_pair2 = self_pair2.compare_rank(other_pair2);
-                let Ordering::Equal = compare_pair2 else {
-                    return Some(compare_pair2);
-                };
-
-                let self_rest = self_cards.remove_cards(self_pair1).remove_cards(self_pair2);
-                let other_rest = other_cards
-                    .remove_cards(other_pair1)
-                    .remove_cards(other_pair2);
-
-                Some(self_rest.compare_rank(&other_rest))
-            }
-            (Outcome::TwoPair(_), _) => Some(Ordering::Greater),
-            (_, Outcome::TwoPair(_)) => Some(Ordering::Less),
-            (Outcome::Pair(self_cards), Outcome::Pair(other_cards)) => {
-                if self_cards == other_cards {
-                    return Some(Ordering::Equal);
-                }
-
-                let self_kinds = self_cards.get_kinds();
-                let other_kinds = other_cards.get_kinds();
-
-                let self_pair = self_kinds.first()?;
-                let other_pair = other_kinds.first()?;
-
-                let compare_pair = self_pair.compare_rank(other_pair);
-                let Ordering::Equal = compare_pair else {
-                    return Some(compare_pair);
-                };
-
-                let self_rest = self_cards.remove_cards(self_pair);
-                let other_rest = other_cards.remove_cards(other_pair);
-
-                Some(self_rest.compare_rank(&other_rest))
-            }
-            (Outcome::Pair(_), _) => Some(Ordering::Greater),
-            (_, Outcome::Pair(_)) => Some(Ordering::Less),
-            (Outcome::HighCard(self_cards), Outcome::HighCard(other_cards)) => {
-                Some(self_cards.compare_rank(other_cards))
-            }
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Outcome {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+        self.category()
+            .cmp(&other.category())
+            .then_with(|| self.rank().cmp(&other.rank()))
+    }
+}
+
+impl Display for Outcome {
+    /// The category and the five cards, like `flush [Ah, Qh, 9h, 5h, 2h]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let category = match self {
+            Outcome::StraightFlush(_) => "straight flush",
+            Outcome::FourOfAKind(_) => "four of a kind",
+            Outcome::FullHouse(_) => "full house",
+            Outcome::Flush(_) => "flush",
+            Outcome::Straight(_) => "straight",
+            Outcome::ThreeOfAKind(_) => "three of a kind",
+            Outcome::TwoPair(_) => "two pair",
+            Outcome::Pair(_) => "pair",
+            Outcome::HighCard(_) => "high card",
+        };
+        write!(f, "{} {}", category, self.cards())
     }
 }
 
@@ -414,4 +319,13 @@ mod tests {
         assert_eq!(quad4.cmp(&quad2), Ordering::Less);
         assert_eq!(quad4.cmp(&quad3), Ordering::Equal);
     }
+
+    #[test]
+    fn test_display() {
+        let flush = Outcome::try_from("Ah Qh 9h 5h 2h 3c 4d".parse::<Cards>().unwrap()).unwrap();
+        assert_eq!("flush [Ah, Qh, 9h, 5h, 2h]", flush.to_string());
+
+        let two_pair = Outcome::try_from("Kc Kd 7s 7h 2c".parse::<Cards>().unwrap()).unwrap();
+        assert_eq!("two pair [Kd, Kc, 7s, 7h, 2c]", two_pair.to_string());
+    }
 }
@@ -0,0 +1,535 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::card::{Card, Rank, Suit};
+use super::cards::{Cards, CardsError};
+
+/// Amount of distinct two card combos in a deck.
+pub const COMBO_COUNT: usize = 1326;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeError {
+    InvalidNotation(String),
+    InvalidWeight(String),
+    InvalidCombo(Cards),
+    Cards(CardsError),
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+impl From<CardsError> for RangeError {
+    fn from(value: CardsError) -> Self {
+        RangeError::Cards(value)
+    }
+}
+
+/// A weighted set of two card combos, written in the usual range notation.
+///
+/// Every combo has a weight between 0 and 1, where 0 means it is not in the range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    weights: Vec<f64>,
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Range {
+            weights: vec![0.0; COMBO_COUNT],
+        }
+    }
+}
+
+impl Range {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A range holding only `combo`, to use a known hand where a range is expected.
+    pub fn from_combo(combo: Cards) -> Result<Self, RangeError> {
+        let mut range = Range::new();
+        range.set(combo, 1.0)?;
+        Ok(range)
+    }
+
+    /// Set the weight of a two card combo, a weight of 0 removes it.
+    pub fn set(&mut self, combo: Cards, weight: f64) -> Result<(), RangeError> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(RangeError::InvalidWeight(weight.to_string()));
+        }
+
+        let index = combo_index(combo).ok_or(RangeError::InvalidCombo(combo))?;
+        self.weights[index] = weight;
+        Ok(())
+    }
+
+    pub fn weight(&self, combo: Cards) -> f64 {
+        combo_index(combo).map_or(0.0, |index| self.weights[index])
+    }
+
+    /// Iterate over the combos in the range with their weight, skipping combos with weight 0.
+    pub fn combos(&self) -> impl Iterator<Item = (Cards, f64)> + '_ {
+        self.weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0.0)
+            .map(|(index, weight)| (combo_from_index(index), *weight))
+    }
+
+    /// Amount of combos with a weight above 0.
+    pub fn len(&self) -> usize {
+        self.weights.iter().filter(|weight| **weight > 0.0).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn add_class(&mut self, class: &Class, weight: f64) {
+        for combo in class.combos() {
+            self.weights[combo_index(combo).unwrap()] = weight;
+        }
+    }
+}
+
+/// Index of a two card combo, `high * (high - 1) / 2 + low` of its card indices.
+fn combo_index(combo: Cards) -> Option<usize> {
+    if combo.card_count() != 2 || !combo.is_valid() {
+        return None;
+    }
+
+    let value = combo.value();
+    let low = value.trailing_zeros() as usize;
+    let high = 63 - value.leading_zeros() as usize;
+    Some(high * (high - 1) / 2 + low)
+}
+
+fn combo_from_index(index: usize) -> Cards {
+    let mut high = 1;
+    while (high + 1) * high / 2 <= index {
+        high += 1;
+    }
+    let low = index - high * (high - 1) / 2;
+    Cards::from((1 << high) | (1 << low))
+}
+
+/// A starting hand class like `QQ`, `AKs`, `AKo` or `AK`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Class {
+    high: Rank,
+    low: Rank,
+    /// `Some(true)` for suited, `Some(false)` for offsuit and `None` for both.
+    suited: Option<bool>,
+}
+
+impl Class {
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    fn combos(&self) -> Vec<Cards> {
+        let mut combos = vec![];
+        for high_suit in Suit::ALL {
+            for low_suit in Suit::ALL {
+                let is_suited = high_suit == low_suit;
+                if self.is_pair() && high_suit <= low_suit {
+                    continue;
+                }
+                if !self.is_pair() && self.suited.is_some_and(|suited| suited != is_suited) {
+                    continue;
+                }
+
+                combos.push(
+                    Cards::from(Card::new(self.high, high_suit))
+                        | Cards::from(Card::new(self.low, low_suit)),
+                );
+            }
+        }
+        combos
+    }
+
+    fn with_low(&self, low: Rank) -> Class {
+        Class { low, ..*self }
+    }
+
+    fn pair(rank: Rank) -> Class {
+        Class {
+            high: rank,
+            low: rank,
+            suited: None,
+        }
+    }
+}
+
+impl FromStr for Class {
+    type Err = RangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RangeError::InvalidNotation(s.to_string());
+
+        let chars: Vec<char> = s.chars().collect();
+        let (first, second, suited) = match chars.as_slice() {
+            [first, second] => (*first, *second, None),
+            [first, second, 's' | 'S'] => (*first, *second, Some(true)),
+            [first, second, 'o' | 'O'] => (*first, *second, Some(false)),
+            _ => return Err(invalid()),
+        };
+
+        let first = Rank::try_from(first)?;
+        let second = Rank::try_from(second)?;
+        if first == second && suited.is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Class {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suited = match self.suited {
+            Some(true) => "s",
+            Some(false) => "o",
+            None => "",
+        };
+        write!(f, "{}{}{}", self.high, self.low, suited)
+    }
+}
+
+/// One comma separated item of range notation, without its weight.
+enum Item {
+    Classes(Vec<Class>),
+    Combo(Cards),
+}
+
+fn parse_item(item: &str) -> Result<Item, RangeError> {
+    let invalid = || RangeError::InvalidNotation(item.to_string());
+
+    let is_combo = item
+        .chars()
+        .nth(1)
+        .is_some_and(|c| Suit::try_from(c).is_ok());
+    if is_combo {
+        let combo: Cards = item.parse()?;
+        if combo.card_count() != 2 {
+            return Err(RangeError::InvalidCombo(combo));
+        }
+        return Ok(Item::Combo(combo));
+    }
+
+    if let Some((from, to)) = item.split_once('-') {
+        let from: Class = from.parse()?;
+        let to: Class = to.parse()?;
+
+        let classes = if from.is_pair() && to.is_pair() {
+            let (low, high) = (from.low.min(to.low), from.low.max(to.low));
+            Rank::ALL
+                .into_iter()
+                .filter(|rank| (low..=high).contains(rank))
+                .map(Class::pair)
+                .collect()
+        } else if from.high == to.high
+            && from.suited == to.suited
+            && !from.is_pair()
+            && !to.is_pair()
+        {
+            let (low, high) = (from.low.min(to.low), from.low.max(to.low));
+            Rank::ALL
+                .into_iter()
+                .filter(|rank| (low..=high).contains(rank))
+                .map(|rank| from.with_low(rank))
+                .collect()
+        } else {
+            return Err(invalid());
+        };
+
+        return Ok(Item::Classes(classes));
+    }
+
+    if let Some(class) = item.strip_suffix('+') {
+        let class: Class = class.parse()?;
+
+        let classes = if class.is_pair() {
+            Rank::ALL
+                .into_iter()
+                .filter(|rank| *rank >= class.low)
+                .map(Class::pair)
+                .collect()
+        } else {
+            Rank::ALL
+                .into_iter()
+                .filter(|rank| *rank >= class.low && *rank < class.high)
+                .map(|rank| class.with_low(rank))
+                .collect()
+        };
+
+        return Ok(Item::Classes(classes));
+    }
+
+    Ok(Item::Classes(vec![item.parse()?]))
+}
+
+impl FromStr for Range {
+    type Err = RangeError;
+
+    /// Parses range notation such as `"QQ+, AKs, A5s-A2s, KQo, AhKh, AKo:0.5"`.
+    ///
+    /// Items are separated by commas, `+` extends a pair up to aces and a kicker up to just below
+    /// the first card, a dash spans two pairs or two kickers of the same first card. A trailing
+    /// `:weight` sets the weight of the item, later items overwrite earlier ones.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::new();
+
+        let items = s.split(',').map(str::trim).filter(|item| !item.is_empty());
+        for item in items {
+            let (item, weight) = match item.split_once(':') {
+                Some((item, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|weight| (0.0..=1.0).contains(weight))
+                        .ok_or(RangeError::InvalidWeight(weight.to_string()))?;
+                    (item.trim(), weight)
+                }
+                None => (item, 1.0),
+            };
+
+            match parse_item(item)? {
+                Item::Classes(classes) => {
+                    for class in classes {
+                        range.add_class(&class, weight);
+                    }
+                }
+                Item::Combo(combo) => range.set(combo, weight)?,
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+/// How the combos of one hand class are present in a range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Coverage {
+    None,
+    Full(f64),
+    Partial,
+}
+
+impl Range {
+    fn coverage(&self, class: &Class) -> Coverage {
+        let weights: Vec<f64> = class
+            .combos()
+            .into_iter()
+            .map(|combo| self.weight(combo))
+            .collect();
+
+        if weights.iter().all(|weight| *weight == 0.0) {
+            Coverage::None
+        } else if weights.iter().all(|weight| *weight == weights[0]) {
+            Coverage::Full(weights[0])
+        } else {
+            Coverage::Partial
+        }
+    }
+}
+
+/// Write runs of classes that differ only in their low card, `top` is the highest low card a `+` may reach.
+fn write_runs(items: &mut Vec<String>, classes: &[(Class, f64)], top: Rank) {
+    let mut start = 0;
+    while start < classes.len() {
+        let (first, weight) = classes[start];
+
+        let mut end = start + 1;
+        while end < classes.len()
+            && classes[end].1 == weight
+            && classes[end].0.low.index() + 1 == classes[end - 1].0.low.index()
+        {
+            end += 1;
+        }
+        let last = classes[end - 1].0;
+
+        let mut item = if end - start == 1 {
+            first.to_string()
+        } else if first.low == top {
+            format!("{}+", last)
+        } else {
+            format!("{}-{}", first, last)
+        };
+
+        if weight != 1.0 {
+            item = format!("{}:{}", item, weight);
+        }
+        items.push(item);
+
+        start = end;
+    }
+}
+
+impl Display for Range {
+    /// Renders the range in compact notation, the inverse of parsing it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut items = vec![];
+        let mut partial = vec![];
+
+        let mut pairs = vec![];
+        for rank in Rank::ALL.into_iter().rev() {
+            let class = Class::pair(rank);
+            match self.coverage(&class) {
+                Coverage::Full(weight) => pairs.push((class, weight)),
+                Coverage::Partial => partial.push(class),
+                Coverage::None => {}
+            }
+        }
+        write_runs(&mut items, &pairs, Rank::Ace);
+
+        for high in Rank::ALL.into_iter().rev() {
+            let mut lanes: [Vec<(Class, f64)>; 3] = [vec![], vec![], vec![]];
+
+            for low in Rank::ALL.into_iter().rev().filter(|low| *low < high) {
+                let class = |suited| Class { high, low, suited };
+                let suited = self.coverage(&class(Some(true)));
+                let offsuit = self.coverage(&class(Some(false)));
+
+                match (suited, offsuit) {
+                    (Coverage::Full(s), Coverage::Full(o)) if s == o => {
+                        lanes[0].push((class(None), s));
+                    }
+                    _ => {
+                        for (coverage, lane, suited) in [(suited, 1, true), (offsuit, 2, false)] {
+                            match coverage {
+                                Coverage::Full(weight) => {
+                                    lanes[lane].push((class(Some(suited)), weight))
+                                }
+                                Coverage::Partial => partial.push(class(Some(suited))),
+                                Coverage::None => {}
+                            }
+                        }
+                    }
+                }
+            }
+
+            let top = high
+                .index()
+                .checked_sub(1)
+                .and_then(Rank::from_index)
+                .unwrap_or(high);
+            for lane in lanes.iter() {
+                write_runs(&mut items, lane, top);
+            }
+        }
+
+        for class in partial {
+            for combo in class.combos() {
+                let weight = self.weight(combo);
+                if weight == 0.0 {
+                    continue;
+                }
+
+                let combo: String = combo.iter().map(|card| card.to_string()).collect();
+                if weight == 1.0 {
+                    items.push(combo);
+                } else {
+                    items.push(format!("{}:{}", combo, weight));
+                }
+            }
+        }
+
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Cards {
+        cards.parse().unwrap()
+    }
+
+    #[test]
+    fn test_combo_index() {
+        for index in 0..COMBO_COUNT {
+            assert_eq!(Some(index), combo_index(combo_from_index(index)));
+        }
+        assert_eq!(None, combo_index(cards("As")));
+    }
+
+    #[test]
+    fn test_parse_classes() {
+        let range: Range = "QQ+, AKs, A5s-A2s, KQo, AhKh, AKo:0.5".parse().unwrap();
+
+        assert_eq!(18 + 4 + 16 + 12 + 12, range.len());
+        assert_eq!(1.0, range.weight(cards("QcQd")));
+        assert_eq!(0.0, range.weight(cards("JcJd")));
+        assert_eq!(1.0, range.weight(cards("As3s")));
+        assert_eq!(0.0, range.weight(cards("As6s")));
+        assert_eq!(0.0, range.weight(cards("As3h")));
+        assert_eq!(1.0, range.weight(cards("KdQh")));
+        assert_eq!(0.5, range.weight(cards("AdKh")));
+        assert_eq!(1.0, range.weight(cards("AhKh")));
+    }
+
+    #[test]
+    fn test_parse_plus_and_dash() {
+        let range: Range = "A9s+, 76, 88-TT".parse().unwrap();
+
+        assert_eq!(5 * 4 + 16 + 3 * 6, range.len());
+        assert_eq!(1.0, range.weight(cards("AhKh")));
+        assert_eq!(1.0, range.weight(cards("Ah9h")));
+        assert_eq!(0.0, range.weight(cards("Ah8h")));
+        assert_eq!(1.0, range.weight(cards("7h6d")));
+        assert_eq!(1.0, range.weight(cards("9h9d")));
+        assert_eq!(0.0, range.weight(cards("JhJd")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            "AKx".parse::<Range>(),
+            Err(RangeError::InvalidNotation(_))
+        ));
+        assert!(matches!(
+            "AKs-KQs".parse::<Range>(),
+            Err(RangeError::InvalidNotation(_))
+        ));
+        assert!(matches!(
+            "AKs:2".parse::<Range>(),
+            Err(RangeError::InvalidWeight(_))
+        ));
+        assert!(matches!(
+            "AsKsQs".parse::<Range>(),
+            Err(RangeError::InvalidCombo(_))
+        ));
+        assert!(matches!(
+            "ZK".parse::<Range>(),
+            Err(RangeError::Cards(CardsError::InvalidRank('Z')))
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        for (notation, expected) in [
+            ("QQ+, AKs, A5s-A2s, KQo", "QQ+, AKs, A5s-A2s, KQo"),
+            ("AKo, AKs", "AK"),
+            ("TT-JJ, 99:0.5", "JJ-TT, 99:0.5"),
+            ("AQs+, KJs, KTs", "AQs+, KJs-KTs"),
+            ("AhKh, AKo:0.25", "AKo:0.25, AhKh"),
+            ("", ""),
+        ] {
+            let range: Range = notation.parse().unwrap();
+            assert_eq!(expected, range.to_string());
+            assert_eq!(Ok(range.clone()), range.to_string().parse());
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Exhaustive checks of [evaluate] and [Outcome] against every 5 and 7 card hand.
+//!
+//! The expected counts are the well-known hand class frequencies, the reference ranking
+//! is a naive implementation of the rules that shares no code with the evaluator.
+
+use std::cmp::Ordering;
+
+use crate::util::all_bit_combo_iterator::IntoAllBitIterator;
+
+use super::cards::Cards;
+use super::constants::*;
+use super::evaluator::*;
+use super::outcome::Outcome;
+
+const CATEGORY_STARTS: [u16; 9] = [
+    HIGH_CARD,
+    PAIR,
+    TWO_PAIR,
+    THREE_OF_A_KIND,
+    STRAIGHT,
+    FLUSH,
+    FULL_HOUSE,
+    FOUR_OF_A_KIND,
+    STRAIGHT_FLUSH,
+];
+
+fn category(rank: u16) -> usize {
+    CATEGORY_STARTS
+        .iter()
+        .rposition(|start| rank >= *start)
+        .unwrap()
+}
+
+fn outcome_category(outcome: &Outcome) -> usize {
+    match outcome {
+        Outcome::HighCard(_) => 0,
+        Outcome::Pair(_) => 1,
+        Outcome::TwoPair(_) => 2,
+        Outcome::ThreeOfAKind(_) => 3,
+        Outcome::Straight(_) => 4,
+        Outcome::Flush(_) => 5,
+        Outcome::FullHouse(_) => 6,
+        Outcome::FourOfAKind(_) => 7,
+        Outcome::StraightFlush(_) => 8,
+    }
+}
+
+/// Rank a five card hand by the textbook rules.
+///
+/// Returns the category in the top bits, followed by the ranks that break ties within it,
+/// most significant first: grouped cards before kickers, bigger groups first.
+fn reference_key(cards: Cards) -> u32 {
+    assert_eq!(5, cards.card_count());
+
+    let mut counts = [0u8; 13];
+    for card in cards {
+        counts[card.rank().index() as usize] += 1;
+    }
+
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|rank| counts[*rank as usize] > 0)
+        .map(|rank| (counts[rank as usize], rank))
+        .collect();
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let is_flush = cards
+        .iter()
+        .all(|card| card.suit() == cards.iter().next().unwrap().suit());
+    let ranks: Vec<u8> = groups.iter().map(|(_, rank)| *rank).collect();
+
+    let straight_high = if groups.len() == 5 && ranks[0] - ranks[4] == 4 {
+        Some(ranks[0])
+    } else if ranks == [12, 3, 2, 1, 0] {
+        Some(3)
+    } else {
+        None
+    };
+
+    let category = match (straight_high, is_flush, groups[0].0, groups[1].0) {
+        (Some(_), true, _, _) => 8,
+        (_, _, 4, _) => 7,
+        (_, _, 3, 2) => 6,
+        (_, true, _, _) => 5,
+        (Some(_), _, _, _) => 4,
+        (_, _, 3, _) => 3,
+        (_, _, 2, 2) => 2,
+        (_, _, 2, _) => 1,
+        _ => 0,
+    };
+
+    let tie_breakers = match straight_high {
+        Some(high) if category == 8 || category == 4 => vec![high],
+        _ => ranks,
+    };
+
+    tie_breakers
+        .iter()
+        .fold(category, |key, rank| (key << 4) | *rank as u32)
+        << (4 * (5 - tie_breakers.len()))
+}
+
+#[test]
+fn test_five_card_hand_counts() {
+    let mut counts = [0u32; 9];
+    let mut seen = vec![false; HAND_RANK_COUNT as usize];
+
+    for hand in FULL_DECK.iter_all_combos(5) {
+        let rank = evaluate(Cards::from(hand));
+        counts[category(rank)] += 1;
+        seen[rank as usize] = true;
+    }
+
+    assert_eq!(
+        [1302540, 1098240, 123552, 54912, 10200, 5108, 3744, 624, 40],
+        counts
+    );
+    assert!(seen.iter().all(|seen| *seen), "every rank is reachable");
+}
+
+#[test]
+fn test_seven_card_hand_counts() {
+    let mut counts = [0u32; 9];
+    let mut royal_flushes = 0;
+    let mut seen = vec![false; HAND_RANK_COUNT as usize];
+
+    for hand in FULL_DECK.iter_all_combos(7) {
+        let rank = evaluate(Cards::from(hand));
+        counts[category(rank)] += 1;
+        seen[rank as usize] = true;
+
+        if rank == HAND_RANK_COUNT - 1 {
+            royal_flushes += 1;
+        }
+    }
+
+    assert_eq!(
+        [23294460, 58627800, 31433400, 6461620, 6180020, 4047644, 3473184, 224848, 41584],
+        counts
+    );
+    assert_eq!(4324, royal_flushes);
+    assert_eq!(4824, seen.iter().filter(|seen| **seen).count());
+}
+
+#[test]
+fn test_five_card_order_matches_reference() {
+    let mut hands: Vec<(u32, u16, Cards)> = FULL_DECK
+        .iter_all_combos(5)
+        .map(Cards::from)
+        .map(|hand| (reference_key(hand), evaluate(hand), hand))
+        .collect();
+    hands.sort_unstable_by_key(|(key, rank, _)| (*key, *rank));
+
+    let mut distinct = 1;
+    for pair in hands.windows(2) {
+        let (key_a, rank_a, _) = pair[0];
+        let (key_b, rank_b, _) = pair[1];
+
+        match key_a.cmp(&key_b) {
+            Ordering::Less => {
+                assert!(rank_a < rank_b, "{} {}", pair[0].2, pair[1].2);
+                distinct += 1;
+            }
+            _ => assert_eq!(rank_a, rank_b, "{} {}", pair[0].2, pair[1].2),
+        }
+    }
+
+    assert_eq!(HAND_RANK_COUNT, distinct);
+}
+
+#[test]
+fn test_five_card_outcome_is_total_order() {
+    let mut hands: Vec<(u32, Outcome)> = FULL_DECK
+        .iter_all_combos(5)
+        .map(Cards::from)
+        .map(|hand| (reference_key(hand), hand.try_into().unwrap()))
+        .collect();
+    hands.sort_unstable_by_key(|(key, _)| *key);
+
+    for (key, outcome) in hands.iter() {
+        assert_eq!(
+            (*key >> 20) as usize,
+            outcome_category(outcome),
+            "{:?}",
+            outcome
+        );
+    }
+
+    // Sorted by the reference, so checking neighbours covers the whole order by transitivity.
+    for pair in hands.windows(2) {
+        let (key_a, outcome_a) = &pair[0];
+        let (key_b, outcome_b) = &pair[1];
+
+        let expected = key_a.cmp(key_b);
+        assert_eq!(
+            expected,
+            outcome_a.cmp(outcome_b),
+            "{:?} {:?}",
+            outcome_a,
+            outcome_b
+        );
+        assert_eq!(expected.reverse(), outcome_b.cmp(outcome_a));
+        assert_eq!(Ordering::Equal, outcome_a.cmp(outcome_a));
+    }
+}
+
+#[test]
+fn test_seven_card_matches_best_five() {
+    for (i, hand) in FULL_DECK.iter_all_combos(7).enumerate() {
+        if !i.is_multiple_of(9973) {
+            continue;
+        }
+
+        let hand = Cards::from(hand);
+        let best = hand
+            .value()
+            .iter_all_combos(5)
+            .map(Cards::from)
+            .max_by_key(|five| reference_key(*five))
+            .unwrap();
+
+        let outcome: Outcome = hand.try_into().unwrap();
+        assert_eq!(evaluate(best), evaluate(hand), "{}", hand);
+        assert_eq!(
+            reference_key(best),
+            reference_key(outcome.cards()),
+            "{}",
+            hand
+        );
+    }
+}
+
+#[test]
+fn test_straight_flush_beside_higher_straight() {
+    let hand: Cards = "9s 8s 7s 6s 5s Th".parse().unwrap();
+    let straight_flush: Cards = "9s 8s 7s 6s 5s".parse().unwrap();
+
+    assert_eq!(Ok(Outcome::StraightFlush(straight_flush)), hand.try_into());
+}
@@ -1,2 +1,8 @@
+pub mod action;
+pub mod agent;
+pub mod betting;
 pub mod game;
 pub mod player;
+pub mod pot;
+pub mod simulation;
+pub mod terminal;
@@ -0,0 +1,226 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A decision of the player whose turn it is.
+///
+/// The amount of [Action::Bet] and [Action::Raise] is the total the player has put in on this
+/// street after acting, so `Raise(300)` raises to 300 no matter how much was in before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u32),
+    Raise(u32),
+    AllIn,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Check => write!(f, "check"),
+            Action::Call => write!(f, "call"),
+            Action::Bet(amount) => write!(f, "bet {}", amount),
+            Action::Raise(amount) => write!(f, "raise {}", amount),
+            Action::AllIn => write!(f, "all-in"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ActionError;
+
+    /// Parses the [Display] form of an action, like `"call"`, `"bet 100"` or `"all-in"`.
+    ///
+    /// Words are matched case-insensitively, `"raise to 300"`, `"allin"` and `"all in"` work too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.to_lowercase();
+        let words: Vec<&str> = lowercase.split_whitespace().collect();
+        let amount = |amount: &str| {
+            amount
+                .parse::<u32>()
+                .map_err(|_| ActionError::InvalidNotation(s.to_string()))
+        };
+
+        match words.as_slice() {
+            ["fold"] => Ok(Action::Fold),
+            ["check"] => Ok(Action::Check),
+            ["call"] => Ok(Action::Call),
+            ["bet", total] => Ok(Action::Bet(amount(total)?)),
+            ["raise", total] | ["raise", "to", total] => Ok(Action::Raise(amount(total)?)),
+            ["all-in"] | ["allin"] | ["all", "in"] => Ok(Action::AllIn),
+            _ => Err(ActionError::InvalidNotation(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    InvalidNotation(String),
+    OutOfTurn {
+        seat: usize,
+        to_act: usize,
+    },
+    CannotCheck {
+        to_call: u32,
+    },
+    NothingToCall,
+    /// There is a bet already, raise instead.
+    BetNotAllowed,
+    /// There is no bet to raise or nobody left to raise against.
+    RaiseNotAllowed,
+    /// Only an incomplete raise came in since the player acted, so they can only call or fold.
+    RaiseNotReopened,
+    AllInNotAllowed,
+    BetOutOfRange {
+        amount: u32,
+        min: u32,
+        max: u32,
+    },
+    RaiseOutOfRange {
+        amount: u32,
+        min: u32,
+        max: u32,
+    },
+}
+
+impl Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+/// What the player whose turn it is may do, see [Game::legal_actions](super::game::Game::legal_actions).
+///
+/// Amounts are totals for the street, like the amounts of [Action::Bet] and [Action::Raise].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegalActions {
+    pub(crate) to_call: u32,
+    pub(crate) reopened: bool,
+    pub(crate) bet: Option<(u32, u32)>,
+    pub(crate) raise: Option<(u32, u32)>,
+    pub(crate) all_in: Option<u32>,
+}
+
+impl LegalActions {
+    /// The chips needed to call, 0 when checking is allowed.
+    pub fn to_call(&self) -> u32 {
+        self.to_call
+    }
+
+    pub fn can_check(&self) -> bool {
+        self.to_call == 0
+    }
+
+    pub fn can_call(&self) -> bool {
+        self.to_call > 0
+    }
+
+    /// The smallest and largest bet allowed, `None` when betting is not.
+    pub fn bet_range(&self) -> Option<(u32, u32)> {
+        self.bet
+    }
+
+    /// The smallest and largest total to raise to, `None` when raising is not allowed.
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        self.raise
+    }
+
+    /// The total after going all-in, `None` when that would be a raise that is not allowed.
+    pub fn all_in(&self) -> Option<u32> {
+        self.all_in
+    }
+
+    /// One of each allowed action, bets and raises at their smallest size.
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = vec![Action::Fold];
+        actions.push(if self.can_check() {
+            Action::Check
+        } else {
+            Action::Call
+        });
+        if let Some((min, _)) = self.bet {
+            actions.push(Action::Bet(min));
+        }
+        if let Some((min, _)) = self.raise {
+            actions.push(Action::Raise(min));
+        }
+        if self.all_in.is_some() {
+            actions.push(Action::AllIn);
+        }
+        actions
+    }
+
+    /// Whether `action` is allowed, and why not if it is not.
+    pub fn check(&self, action: Action) -> Result<(), ActionError> {
+        match action {
+            Action::Fold => {}
+            Action::Check if !self.can_check() => Err(ActionError::CannotCheck {
+                to_call: self.to_call,
+            })?,
+            Action::Check => {}
+            Action::Call if !self.can_call() => Err(ActionError::NothingToCall)?,
+            Action::Call => {}
+            Action::Bet(amount) => {
+                let (min, max) = self.bet.ok_or(ActionError::BetNotAllowed)?;
+                if !(min..=max).contains(&amount) {
+                    Err(ActionError::BetOutOfRange { amount, min, max })?;
+                }
+            }
+            Action::Raise(amount) => {
+                let (min, max) = self.raise.ok_or(self.raise_error())?;
+                if !(min..=max).contains(&amount) {
+                    Err(ActionError::RaiseOutOfRange { amount, min, max })?;
+                }
+            }
+            Action::AllIn if self.all_in.is_none() => {
+                Err(if self.reopened {
+                    ActionError::AllInNotAllowed
+                } else {
+                    ActionError::RaiseNotReopened
+                })?;
+            }
+            Action::AllIn => {}
+        }
+        Ok(())
+    }
+
+    fn raise_error(&self) -> ActionError {
+        if self.reopened {
+            ActionError::RaiseNotAllowed
+        } else {
+            ActionError::RaiseNotReopened
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for action in [
+            Action::Fold,
+            Action::Check,
+            Action::Call,
+            Action::Bet(100),
+            Action::Raise(300),
+            Action::AllIn,
+        ] {
+            assert_eq!(Ok(action), action.to_string().parse());
+        }
+
+        assert_eq!(Ok(Action::Raise(300)), " Raise to 300 ".parse());
+        assert_eq!(Ok(Action::AllIn), "ALL IN".parse());
+        for invalid in ["", "raise", "bet -5", "call 20", "shove"] {
+            assert_eq!(
+                Err(ActionError::InvalidNotation(invalid.to_string())),
+                invalid.parse::<Action>()
+            );
+        }
+    }
+}
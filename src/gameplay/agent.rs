@@ -0,0 +1,406 @@
+use crate::engine::cards::Cards;
+use crate::engine::monte_carlo::{sample_runout, SampleConfig};
+use crate::engine::out_runner::{remaining_deck, runout};
+use crate::util::rng::{Rng, Xoshiro256};
+
+use super::action::{Action, LegalActions};
+use super::game::{GameEvent, Street};
+
+/// Decides the actions of one seat, see [Game::play_hand](super::game::Game::play_hand).
+pub trait Agent {
+    /// The action to take when it is the turn of the observed seat.
+    fn act(&mut self, observation: &Observation) -> Action;
+}
+
+/// What the seat whose turn it is can see of the hand, see
+/// [Game::observe](super::game::Game::observe).
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub(crate) seat: usize,
+    pub(crate) hole_cards: Cards,
+    pub(crate) board: Cards,
+    pub(crate) street: Street,
+    pub(crate) dealer: usize,
+    pub(crate) big_blind: u32,
+    pub(crate) pot: u32,
+    pub(crate) current_bet: u32,
+    pub(crate) stacks: Vec<u32>,
+    pub(crate) street_bets: Vec<u32>,
+    pub(crate) in_hand: Vec<bool>,
+    pub(crate) history: Vec<GameEvent>,
+    pub(crate) legal_actions: LegalActions,
+}
+
+impl Observation {
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    pub fn hole_cards(&self) -> Cards {
+        self.hole_cards
+    }
+
+    pub fn board(&self) -> Cards {
+        self.board
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    pub fn big_blind(&self) -> u32 {
+        self.big_blind
+    }
+
+    /// All chips put in during the hand, the current street included.
+    pub fn pot(&self) -> u32 {
+        self.pot
+    }
+
+    /// The amount every player has to match on this street.
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    /// The chips behind of the observed seat.
+    pub fn stack(&self) -> u32 {
+        self.stacks[self.seat]
+    }
+
+    /// The chips behind of every seat.
+    pub fn stacks(&self) -> &[u32] {
+        &self.stacks
+    }
+
+    /// The chips every seat put in on this street.
+    pub fn street_bets(&self) -> &[u32] {
+        &self.street_bets
+    }
+
+    /// Which seats still play for the pot.
+    pub fn in_hand(&self) -> &[bool] {
+        &self.in_hand
+    }
+
+    /// The events of this hand so far, without the hole cards of other seats.
+    pub fn history(&self) -> &[GameEvent] {
+        &self.history
+    }
+
+    pub fn legal_actions(&self) -> &LegalActions {
+        &self.legal_actions
+    }
+}
+
+/// Picks uniformly among the legal actions, and bets and raises a uniform amount in their range.
+#[derive(Debug, Clone)]
+pub struct RandomAgent<R: Rng = Xoshiro256> {
+    rng: R,
+}
+
+impl RandomAgent {
+    pub fn from_seed(seed: u64) -> Self {
+        RandomAgent::new(Xoshiro256::from_seed(seed))
+    }
+}
+
+impl<R: Rng> RandomAgent<R> {
+    pub fn new(rng: R) -> Self {
+        RandomAgent { rng }
+    }
+
+    fn pick(&mut self, (min, max): (u32, u32)) -> u32 {
+        min + self.rng.below((max - min) as u64 + 1) as u32
+    }
+}
+
+impl<R: Rng> Agent for RandomAgent<R> {
+    fn act(&mut self, observation: &Observation) -> Action {
+        let legal = observation.legal_actions();
+        let actions = legal.actions();
+
+        match actions[self.rng.below(actions.len() as u64) as usize] {
+            Action::Bet(_) => Action::Bet(self.pick(legal.bet_range().unwrap())),
+            Action::Raise(_) => Action::Raise(self.pick(legal.raise_range().unwrap())),
+            action => action,
+        }
+    }
+}
+
+/// Never bets or folds, checks when it can and calls otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallingStation;
+
+impl Agent for CallingStation {
+    fn act(&mut self, observation: &Observation) -> Action {
+        if observation.legal_actions().can_check() {
+            Action::Check
+        } else {
+            Action::Call
+        }
+    }
+}
+
+/// The thresholds and sizes of an [EquityAgent].
+#[derive(Debug, Clone)]
+pub struct EquityConfig {
+    /// Bet or raise with at least this equity.
+    pub raise_equity: f64,
+    /// Call when the equity beats the pot odds by at least this much.
+    pub call_margin: f64,
+    /// Bets and raises add this fraction of the pot after calling.
+    pub bet_size: f64,
+    /// Count every runout exactly from this street on, sample before it.
+    pub exact_from: Street,
+    /// Trials per decision when sampling.
+    pub samples: u64,
+    pub seed: u64,
+}
+
+impl Default for EquityConfig {
+    fn default() -> Self {
+        Self {
+            raise_equity: 0.65,
+            call_margin: 0.0,
+            bet_size: 0.75,
+            exact_from: Street::Turn,
+            samples: 2000,
+            seed: 0,
+        }
+    }
+}
+
+/// Plays its equity against random hands: bets it when strong, calls when the pot odds are good
+/// enough and gives up otherwise.
+#[derive(Debug, Clone)]
+pub struct EquityAgent {
+    config: EquityConfig,
+    rng: Xoshiro256,
+}
+
+impl EquityAgent {
+    pub fn new(config: EquityConfig) -> Self {
+        EquityAgent {
+            rng: Xoshiro256::from_seed(config.seed),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &EquityConfig {
+        &self.config
+    }
+
+    /// A quick approximation of the share of the pot the hole cards win at showdown against every
+    /// opponent still in the hand, each holding a random hand.
+    ///
+    /// The equity against one opponent comes from [runout] or [sample_runout] and is raised to the
+    /// power of the number of opponents, as if beating each of them were independent. That ignores
+    /// split pots and the cards the opponents hold between them, so it is a heuristic rather than
+    /// the multiway equity.
+    pub fn equity(&mut self, observation: &Observation) -> f64 {
+        let (hole_cards, board) = (observation.hole_cards(), observation.board());
+        let deck = remaining_deck(&[hole_cards], board, Cards::default())
+            .expect("the cards of a game never overlap");
+
+        let heads_up = if observation.street() >= self.config.exact_from {
+            runout(hole_cards, board, deck).map(|chance| chance.equity())
+        } else {
+            let config = SampleConfig {
+                seed: self.rng.next_u64(),
+                max_trials: self.config.samples,
+                ..SampleConfig::default()
+            };
+            sample_runout(hole_cards, board, deck, &config).map(|estimate| estimate.equity())
+        }
+        .expect("the cards of a game are a valid hand and board");
+
+        let opponents = observation
+            .in_hand()
+            .iter()
+            .filter(|in_hand| **in_hand)
+            .count()
+            - 1;
+        heads_up.powi(opponents as i32)
+    }
+
+    /// The bet or raise of [EquityConfig::bet_size] within the legal range, or an all-in raise
+    /// when no full raise is allowed.
+    fn aggressive(&self, observation: &Observation) -> Option<Action> {
+        let legal = observation.legal_actions();
+        let pot = observation.pot() + legal.to_call();
+        let target = observation.current_bet() + (pot as f64 * self.config.bet_size) as u32;
+
+        let clamp = |(min, max): (u32, u32)| target.clamp(min, max);
+        if let Some(range) = legal.bet_range() {
+            return Some(Action::Bet(clamp(range)));
+        }
+        if let Some(range) = legal.raise_range() {
+            return Some(Action::Raise(clamp(range)));
+        }
+        legal
+            .all_in()
+            .filter(|all_in| *all_in > observation.current_bet())
+            .map(|_| Action::AllIn)
+    }
+}
+
+impl Agent for EquityAgent {
+    fn act(&mut self, observation: &Observation) -> Action {
+        let equity = self.equity(observation);
+        let legal = observation.legal_actions();
+
+        if equity >= self.config.raise_equity {
+            if let Some(action) = self.aggressive(observation) {
+                return action;
+            }
+        }
+
+        let to_call = legal.to_call().min(observation.stack());
+        let pot_odds = to_call as f64 / (observation.pot() + to_call) as f64;
+        if legal.can_check() {
+            Action::Check
+        } else if equity >= pot_odds + self.config.call_margin {
+            Action::Call
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::game::{Game, GameConfig, GameError};
+    use crate::gameplay::player::Player;
+
+    use super::*;
+
+    fn table(players: usize) -> Game {
+        let mut game = Game::new(GameConfig {
+            small_blind: 5,
+            big_blind: 10,
+            seed: 3,
+            ..GameConfig::default()
+        });
+        for i in 0..players {
+            game.add_player(Player::new(format!("player {}", i), 1000));
+        }
+        game
+    }
+
+    #[test]
+    fn test_random_agents_play_legal_hands() {
+        let mut game = table(6);
+        let mut agents: Vec<Box<dyn Agent>> = (0..6)
+            .map(|seat| Box::new(RandomAgent::from_seed(seat)) as Box<dyn Agent>)
+            .collect();
+
+        for _ in 0..200 {
+            if game.new_round().is_err() {
+                break;
+            }
+            game.finish_hand(&mut agents).unwrap();
+            assert_eq!(None, game.street());
+            assert_eq!(6000, game.players().iter().map(Player::stack).sum::<u32>());
+        }
+    }
+
+    #[test]
+    fn test_calling_stations_reach_showdown() {
+        let mut game = table(3);
+        let mut agents: Vec<Box<dyn Agent>> = (0..3)
+            .map(|_| Box::new(CallingStation) as Box<dyn Agent>)
+            .collect();
+
+        game.play_hand(&mut agents).unwrap();
+
+        assert_eq!(5, game.board().card_count());
+        assert!(game
+            .history()
+            .iter()
+            .any(|event| matches!(event, GameEvent::Showdown { .. })));
+    }
+
+    fn observation(hole_cards: &str, board: &str, pot: u32, to_call: u32) -> Observation {
+        Observation {
+            seat: 0,
+            hole_cards: hole_cards.parse().unwrap(),
+            board: board.parse().unwrap(),
+            street: Street::River,
+            dealer: 1,
+            big_blind: 10,
+            pot,
+            current_bet: to_call,
+            stacks: vec![1000, 1000],
+            street_bets: vec![0, to_call],
+            in_hand: vec![true, true],
+            history: vec![],
+            legal_actions: LegalActions {
+                to_call,
+                reopened: true,
+                bet: (to_call == 0).then_some((10, 1000)),
+                raise: (to_call > 0).then_some((2 * to_call, 1000)),
+                all_in: Some(1000),
+            },
+        }
+    }
+
+    #[test]
+    fn test_equity_agent() {
+        let mut agent = EquityAgent::new(EquityConfig::default());
+
+        // Quads bet three quarters of the pot, or raise by that much after calling.
+        let quads = observation("AcKc", "AsAdAh2c7d", 100, 0);
+        assert_eq!(1.0, agent.equity(&quads));
+        assert_eq!(Action::Bet(75), agent.act(&quads));
+        assert_eq!(
+            Action::Raise(200),
+            agent.act(&observation("AcKc", "AsAdAh2c7d", 150, 50))
+        );
+
+        // Seven high checks, and folds to a bet since it needs a third of the pot.
+        let air = observation("2c3d", "AsKsQsJd9h", 100, 0);
+        assert_eq!(Action::Check, agent.act(&air));
+        assert_eq!(
+            Action::Fold,
+            agent.act(&observation("2c3d", "AsKsQsJd9h", 150, 50))
+        );
+
+        // Underpair calls a pot sized bet, but not when it has to beat the odds by a margin.
+        let pair = observation("5c5d", "AsKs7h4d2c", 200, 100);
+        let equity = agent.equity(&pair);
+        assert!((0.5..0.65).contains(&equity), "{}", equity);
+        assert_eq!(Action::Call, agent.act(&pair));
+
+        let mut tight = EquityAgent::new(EquityConfig {
+            call_margin: 0.3,
+            ..EquityConfig::default()
+        });
+        assert_eq!(Action::Fold, tight.act(&pair));
+    }
+
+    #[test]
+    fn test_equity_agents_play_hands() {
+        let mut game = table(4);
+        let mut agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(EquityAgent::new(EquityConfig::default())),
+            Box::new(CallingStation),
+            Box::new(RandomAgent::from_seed(1)),
+            Box::new(EquityAgent::new(EquityConfig {
+                seed: 1,
+                ..EquityConfig::default()
+            })),
+        ];
+
+        for _ in 0..20 {
+            match game.play_hand(&mut agents) {
+                Err(GameError::NotEnoughPlayers) => break,
+                result => result.unwrap(),
+            }
+            assert_eq!(4000, game.players().iter().map(Player::stack).sum::<u32>());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use super::game::Street;
+
+/// How much a player may bet or raise, part of the [GameConfig](super::game::GameConfig).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BettingStructure {
+    /// Any bet from the big blind and any raise of at least the last increment, up to the stack.
+    #[default]
+    NoLimit,
+    /// Like no-limit, but a raise can at most add the size of the pot after calling.
+    PotLimit,
+    /// Bets and raises of exactly `small_bet` before the turn and `big_bet` after, at most
+    /// `raise_cap` bets and raises per street unless only two players are left.
+    FixedLimit {
+        small_bet: u32,
+        big_bet: u32,
+        raise_cap: u32,
+    },
+}
+
+/// The state of the betting on the current street that limits the next bet or raise.
+#[derive(Debug, Clone)]
+pub(crate) struct BettingRound {
+    pub street: Street,
+    pub big_blind: u32,
+    /// The bet everyone has to match.
+    pub current_bet: u32,
+    /// The smallest increment of a full raise.
+    pub min_raise: u32,
+    pub to_call: u32,
+    /// All chips put in during the hand, the current street included.
+    pub pot: u32,
+    /// Bets and raises made on this street, the big blind counts as the first bet preflop.
+    pub bets: u32,
+    pub players_in_hand: usize,
+}
+
+impl BettingStructure {
+    /// The size of one bet on `street`, which is also the first full raise increment.
+    pub fn bet_size(&self, street: Street, big_blind: u32) -> u32 {
+        match self {
+            BettingStructure::FixedLimit {
+                small_bet, big_bet, ..
+            } => match street {
+                Street::Preflop | Street::Flop => *small_bet,
+                Street::Turn | Street::River => *big_bet,
+            },
+            _ => big_blind,
+        }
+    }
+
+    /// The smallest and largest total to bet or raise to, before looking at the stack.
+    ///
+    /// `None` when the raises of this street are capped.
+    pub(crate) fn limits(&self, round: &BettingRound) -> Option<(u32, u32)> {
+        let min = if round.current_bet == 0 {
+            self.bet_size(round.street, round.big_blind)
+        } else {
+            round.current_bet + round.min_raise
+        };
+
+        match self {
+            BettingStructure::NoLimit => Some((min, u32::MAX)),
+            // Call first, then raise by the pot that makes.
+            BettingStructure::PotLimit => Some((
+                min,
+                (round.current_bet + round.pot + round.to_call).max(min),
+            )),
+            BettingStructure::FixedLimit { raise_cap, .. } => {
+                if round.bets >= *raise_cap && round.players_in_hand > 2 {
+                    return None;
+                }
+                let total = round.current_bet + self.bet_size(round.street, round.big_blind);
+                Some((total, total))
+            }
+        }
+    }
+}
+
+impl Display for BettingStructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BettingStructure::NoLimit => write!(f, "no-limit"),
+            BettingStructure::PotLimit => write!(f, "pot-limit"),
+            BettingStructure::FixedLimit {
+                small_bet,
+                big_bet,
+                raise_cap,
+            } => write!(f, "fixed-limit {}/{} cap {}", small_bet, big_bet, raise_cap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(current_bet: u32, to_call: u32, pot: u32) -> BettingRound {
+        BettingRound {
+            street: Street::Preflop,
+            big_blind: 10,
+            current_bet,
+            min_raise: 10,
+            to_call,
+            pot,
+            bets: 1,
+            players_in_hand: 3,
+        }
+    }
+
+    #[test]
+    fn test_pot_limit() {
+        // Blinds of 5 and 10: calling makes a pot of 25, so the first raise goes to 35.
+        assert_eq!(
+            Some((20, 35)),
+            BettingStructure::PotLimit.limits(&round(10, 10, 15))
+        );
+        // Facing a bet of 50 into 100 with 0 in: call 50 for a pot of 200, raise to 250.
+        assert_eq!(
+            Some((60, 250)),
+            BettingStructure::PotLimit.limits(&BettingRound {
+                street: Street::Flop,
+                ..round(50, 50, 150)
+            })
+        );
+        assert_eq!(
+            Some((10, 40)),
+            BettingStructure::PotLimit.limits(&BettingRound {
+                street: Street::Flop,
+                bets: 0,
+                ..round(0, 0, 40)
+            })
+        );
+    }
+
+    #[test]
+    fn test_fixed_limit() {
+        let structure = BettingStructure::FixedLimit {
+            small_bet: 10,
+            big_bet: 20,
+            raise_cap: 4,
+        };
+
+        assert_eq!(Some((20, 20)), structure.limits(&round(10, 10, 15)));
+        assert_eq!(
+            Some((40, 40)),
+            structure.limits(&BettingRound {
+                street: Street::Turn,
+                bets: 1,
+                ..round(20, 20, 100)
+            })
+        );
+
+        let capped = BettingRound {
+            bets: 4,
+            ..round(40, 30, 100)
+        };
+        assert_eq!(None, structure.limits(&capped));
+        assert_eq!(
+            Some((50, 50)),
+            structure.limits(&BettingRound {
+                players_in_hand: 2,
+                ..capped
+            })
+        );
+    }
+}
@@ -1,37 +1,1501 @@
-use crate::engine::{cards::Cards, constants::FULL_DECK};
+use std::fmt::Display;
 
+use crate::engine::cards::Cards;
+use crate::engine::deck::{Deck, DeckError};
+use crate::engine::outcome::{Outcome, OutcomeError};
+
+use super::action::{Action, ActionError, LegalActions};
+use super::agent::{Agent, Observation};
+use super::betting::{BettingRound, BettingStructure};
 use super::player::Player;
+use super::pot::{build_pots, distribute, OddChipRule, Pot};
+
+/// Every player is dealt 2 cards and the board takes 5 plus 3 burns.
+const MAX_PLAYERS: usize = 22;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    NotEnoughPlayers,
+    TooManyPlayers,
+    HandInProgress,
+    NoHandInProgress,
+    InvalidSeat(usize),
+    /// There is no agent for the seat whose turn it is.
+    MissingAgent(usize),
+    IllegalAction(ActionError),
+    Deck(DeckError),
+    Outcome(OutcomeError),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<DeckError> for GameError {
+    fn from(value: DeckError) -> Self {
+        GameError::Deck(value)
+    }
+}
+
+impl From<ActionError> for GameError {
+    fn from(value: ActionError) -> Self {
+        GameError::IllegalAction(value)
+    }
+}
+
+impl From<OutcomeError> for GameError {
+    fn from(value: OutcomeError) -> Self {
+        GameError::Outcome(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
 
 #[derive(Debug, Clone)]
 pub enum GameEvent {
-    Bet(u32),
+    NewHand {
+        dealer: usize,
+        betting: BettingStructure,
+    },
+    Blind {
+        seat: usize,
+        amount: u32,
+    },
+    /// Dead chips in the pot that do not count towards the bet of the player.
+    Ante {
+        seat: usize,
+        amount: u32,
+    },
+    Straddle {
+        seat: usize,
+        amount: u32,
+    },
+    /// A player sitting out was passed by a blind, they post it when they sit back in.
+    MissedBlind {
+        seat: usize,
+        amount: u32,
+    },
+    /// A missed small blind posted on the way back in, dead like an ante.
+    DeadBlind {
+        seat: usize,
+        amount: u32,
+    },
+    HoleCards {
+        seat: usize,
+        cards: Cards,
+    },
+    Action {
+        seat: usize,
+        action: Action,
+    },
     Flop(Cards),
     Turn(Cards),
     River(Cards),
+    /// The part of a bet nobody called went back to the player.
+    UncalledBet {
+        seat: usize,
+        amount: u32,
+    },
+    Showdown {
+        seat: usize,
+        outcome: Outcome,
+    },
+    Win {
+        seat: usize,
+        amount: u32,
+    },
+}
+
+/// A voluntary blind of twice the big blind, posted before the cards are dealt.
+///
+/// The straddle acts as the big blind: the action starts to its left, it acts last before the
+/// flop and a raise has to be at least the size of the straddle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Straddle {
+    /// Posted by the player left of the big blind.
+    UnderTheGun,
+    /// Posted by the button, the action then starts with the small blind.
+    Button,
+}
+
+/// The table stakes, house rules and the seed of the deck.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    /// Posted by every player dealt in.
+    pub ante: u32,
+    /// Posted by the big blind for the whole table, after the big blind itself.
+    pub big_blind_ante: u32,
+    /// Only with at least three players dealt in who can cover it.
+    pub straddle: Option<Straddle>,
+    pub betting: BettingStructure,
+    pub odd_chip: OddChipRule,
+    pub seed: u64,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            small_blind: 1,
+            big_blind: 2,
+            ante: 0,
+            big_blind_ante: 0,
+            straddle: None,
+            betting: BettingStructure::default(),
+            odd_chip: OddChipRule::default(),
+            seed: 0,
+        }
+    }
+}
+
+/// The state of one player in the current hand.
+#[derive(Debug, Clone, Default)]
+struct Seat {
+    hole_cards: Cards,
+    /// Chips put in on the current street.
+    street_bet: u32,
+    /// Chips put in over the whole hand.
+    contributed: u32,
+    dealt_in: bool,
+    folded: bool,
+    all_in: bool,
+    /// Acted since the last full bet or raise, the round closes once everyone has.
+    acted: bool,
+    /// The bet to match when the player last acted, to tell whether raises since reopened it.
+    matched: u32,
+}
+
+impl Seat {
+    fn in_hand(&self) -> bool {
+        self.dealt_in && !self.folded
+    }
+
+    fn can_act(&self) -> bool {
+        self.in_hand() && !self.all_in
+    }
+}
+
+/// The blinds a player sitting out owes.
+#[derive(Debug, Clone, Copy, Default)]
+struct MissedBlinds {
+    small: bool,
+    big: bool,
 }
 
-#[derive(Debug, Default)]
+/// A hold'em table with the [BettingStructure] of its [GameConfig], playing one hand at a time.
+///
+/// Start a hand with [Game::new_round], then feed the player from [Game::to_act] an [Action]
+/// with [Game::act] until the hand is over. Every step is recorded in [Game::history].
+#[derive(Debug)]
 pub struct Game {
+    config: GameConfig,
     players: Vec<Player>,
+    sitting_out: Vec<bool>,
+    missed_blinds: Vec<MissedBlinds>,
     dealer: usize,
-    deck: Cards,
+    /// The small and big blind of the last hand, to see which blinds passed players sitting out.
+    blinds: Option<(usize, usize)>,
+    deck: Deck,
     game_history: Vec<GameEvent>,
+    /// Where the current or last hand starts in the history.
+    hand_start: usize,
+    hands_played: u64,
+    seats: Vec<Seat>,
+    board: Cards,
+    street: Option<Street>,
+    to_act: Option<usize>,
+    /// The highest [Seat::street_bet], what everyone has to match.
+    current_bet: u32,
+    /// The smallest raise increment allowed, the size of the last bet or raise.
+    min_raise: u32,
+    /// Full bets and raises on this street, the big blind counts as the first one preflop.
+    bets: u32,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new(GameConfig::default())
+    }
 }
 
 impl Game {
-    pub fn new_round(&mut self) {
-        self.deck = Cards::from(FULL_DECK);
-        for p in self.players.iter() {
+    pub fn new(config: GameConfig) -> Self {
+        Game {
+            deck: Deck::from_seed(config.seed),
+            config,
+            players: vec![],
+            sitting_out: vec![],
+            missed_blinds: vec![],
+            dealer: 0,
+            blinds: None,
+            game_history: vec![],
+            hand_start: 0,
+            hands_played: 0,
+            seats: vec![],
+            board: Cards::default(),
+            street: None,
+            to_act: None,
+            current_bet: 0,
+            min_raise: 0,
+            bets: 0,
+        }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Move the button, post the forced bets and deal the hole cards of the next hand.
+    ///
+    /// Players without chips or sitting out are not dealt in. A player back from sitting out
+    /// posts the big blind they missed live and the small blind dead, unless they are in the
+    /// blinds anyway.
+    pub fn new_round(&mut self) -> Result<(), GameError> {
+        if self.street.is_some() {
+            Err(GameError::HandInProgress)?;
+        }
+
+        let dealt_in = (0..self.players.len())
+            .filter(|seat| self.can_be_dealt_in(*seat))
+            .count();
+        if dealt_in < 2 {
+            Err(GameError::NotEnoughPlayers)?;
+        }
+        if dealt_in > MAX_PLAYERS {
+            Err(GameError::TooManyPlayers)?;
+        }
+
+        self.seats = (0..self.players.len())
+            .map(|seat| Seat {
+                dealt_in: self.can_be_dealt_in(seat),
+                ..Seat::default()
+            })
+            .collect();
+
+        if self.hands_played > 0 || !self.seats[self.dealer].dealt_in {
+            self.dealer = self.next_seat(self.dealer, Seat::in_hand).unwrap();
+        }
+        self.hand_start = self.game_history.len();
+        self.game_history.push(GameEvent::NewHand {
+            dealer: self.dealer,
+            betting: self.config.betting,
+        });
+
+        self.deck.reset();
+        self.board = Cards::default();
+        self.street = Some(Street::Preflop);
+
+        // Heads-up the button posts the small blind and acts first before the flop.
+        let small_blind = if dealt_in == 2 {
+            self.dealer
+        } else {
+            self.next_seat(self.dealer, Seat::in_hand).unwrap()
+        };
+        let big_blind = self.next_seat(small_blind, Seat::in_hand).unwrap();
+        self.record_missed_blinds(small_blind, big_blind);
+
+        if self.config.ante > 0 {
+            for seat in self.dealing_order() {
+                self.post_ante(seat, self.config.ante);
+            }
+        }
+        self.post_blind(small_blind, self.config.small_blind);
+        self.post_blind(big_blind, self.config.big_blind);
+        if self.config.big_blind_ante > 0 {
+            self.post_ante(big_blind, self.config.big_blind_ante);
+        }
+        self.post_missed_blinds(small_blind, big_blind);
+
+        self.current_bet = self.config.big_blind;
+        self.min_raise = self
+            .config
+            .betting
+            .bet_size(Street::Preflop, self.config.big_blind);
+        self.bets = 1;
+
+        let mut last_forced = big_blind;
+        if let Some(seat) = self.straddler(dealt_in, big_blind) {
+            let amount = 2 * self.config.big_blind;
+            self.commit(seat, amount - self.seats[seat].street_bet);
+            self.game_history.push(GameEvent::Straddle { seat, amount });
+
+            self.current_bet = amount;
+            self.bets += 1;
+            // Under fixed-limit the straddle is just a raise of one small bet.
+            if !matches!(self.config.betting, BettingStructure::FixedLimit { .. }) {
+                self.min_raise = amount;
+            }
+            last_forced = seat;
+        }
+
+        self.deal_hole_cards()?;
+
+        self.to_act = Some(last_forced);
+        self.advance()
+    }
+
+    fn can_be_dealt_in(&self, seat: usize) -> bool {
+        self.players[seat].stack() > 0 && !self.sitting_out[seat]
+    }
+
+    /// Every seat dealt in, starting left of the button.
+    fn dealing_order(&self) -> Vec<usize> {
+        let first = self.next_seat(self.dealer, Seat::in_hand).unwrap();
+        (0..self.seats.len())
+            .map(|i| (first + i) % self.seats.len())
+            .filter(|seat| self.seats[*seat].dealt_in)
+            .collect()
+    }
+
+    /// The seat that straddles this hand, if any.
+    fn straddler(&self, dealt_in: usize, big_blind: usize) -> Option<usize> {
+        if dealt_in < 3 {
+            return None;
         }
+        let seat = match self.config.straddle? {
+            Straddle::UnderTheGun => self.next_seat(big_blind, Seat::in_hand)?,
+            Straddle::Button => self.dealer,
+        };
+
+        let owed = 2 * self.config.big_blind - self.seats[seat].street_bet;
+        (self.players[seat].stack() >= owed).then_some(seat)
     }
 
-    pub fn add_player(&mut self, player: Player) -> () {
+    /// Charge the players sitting out that the blinds moved past since the last hand.
+    fn record_missed_blinds(&mut self, small_blind: usize, big_blind: usize) {
+        let Some((last_small, last_big)) = self.blinds.replace((small_blind, big_blind)) else {
+            return;
+        };
+
+        let seats = self.seats.len();
+        let passed = |from: usize, to: usize| -> Vec<usize> {
+            (1..seats)
+                .map(|i| (from + i) % seats)
+                .take_while(|seat| *seat != to && from != to)
+                .collect()
+        };
+
+        for seat in passed(last_small, small_blind) {
+            if self.sitting_out[seat] {
+                self.missed_blinds[seat].small = true;
+                self.game_history.push(GameEvent::MissedBlind {
+                    seat,
+                    amount: self.config.small_blind,
+                });
+            }
+        }
+        for seat in passed(last_big, big_blind) {
+            if self.sitting_out[seat] {
+                self.missed_blinds[seat].big = true;
+                self.game_history.push(GameEvent::MissedBlind {
+                    seat,
+                    amount: self.config.big_blind,
+                });
+            }
+        }
+    }
+
+    fn post_missed_blinds(&mut self, small_blind: usize, big_blind: usize) {
+        for seat in self.dealing_order() {
+            let missed = std::mem::take(&mut self.missed_blinds[seat]);
+            if seat == small_blind || seat == big_blind {
+                continue;
+            }
+
+            if missed.big {
+                self.post_blind(seat, self.config.big_blind);
+            }
+            if missed.small {
+                let amount = self.post_dead(seat, self.config.small_blind);
+                self.game_history
+                    .push(GameEvent::DeadBlind { seat, amount });
+            }
+        }
+    }
+
+    fn post_ante(&mut self, seat: usize, amount: u32) {
+        let posted = self.post_dead(seat, amount);
+        self.game_history.push(GameEvent::Ante {
+            seat,
+            amount: posted,
+        });
+    }
+
+    fn post_blind(&mut self, seat: usize, amount: u32) {
+        let posted = self.commit(seat, amount);
+        self.game_history.push(GameEvent::Blind {
+            seat,
+            amount: posted,
+        });
+    }
+
+    fn deal_hole_cards(&mut self) -> Result<(), GameError> {
+        let order = self.dealing_order();
+
+        // One card at a time, like at a real table.
+        for _ in 0..2 {
+            for seat in order.iter() {
+                let card = self.deck.deal_card()?;
+                self.seats[*seat].hole_cards |= Cards::from(card);
+            }
+        }
+
+        for seat in order {
+            let cards = self.seats[seat].hole_cards;
+            self.game_history.push(GameEvent::HoleCards { seat, cards });
+        }
+        Ok(())
+    }
+
+    /// Move up to `amount` more chips of `seat` into the pot, returns the chips moved.
+    fn commit(&mut self, seat: usize, amount: u32) -> u32 {
+        let taken = self.post_dead(seat, amount);
+        self.seats[seat].street_bet += taken;
+        taken
+    }
+
+    /// Like [Game::commit], but the chips do not count towards the bet of `seat`.
+    fn post_dead(&mut self, seat: usize, amount: u32) -> u32 {
+        let taken = self.players[seat].take(amount);
+        let state = &mut self.seats[seat];
+        state.contributed += taken;
+        state.all_in = self.players[seat].stack() == 0;
+        taken
+    }
+
+    /// The first seat after `from`, going around the table, for which `accept` holds.
+    fn next_seat(&self, from: usize, accept: impl Fn(&Seat) -> bool) -> Option<usize> {
+        (1..=self.seats.len())
+            .map(|i| (from + i) % self.seats.len())
+            .find(|seat| accept(&self.seats[*seat]))
+    }
+
+    /// Apply `action` for `seat`, which has to be the seat whose turn it is.
+    pub fn act(&mut self, seat: usize, action: Action) -> Result<(), GameError> {
+        let to_act = self.to_act.ok_or(GameError::NoHandInProgress)?;
+        if seat != to_act {
+            Err(ActionError::OutOfTurn { seat, to_act })?;
+        }
+        self.legal_actions().check(action)?;
+
+        let state = &self.seats[seat];
+        let target = match action {
+            Action::Fold | Action::Check => state.street_bet,
+            Action::Call => self.current_bet,
+            Action::Bet(amount) | Action::Raise(amount) => amount,
+            Action::AllIn => state.street_bet + self.players[seat].stack(),
+        };
+
+        if action == Action::Fold {
+            self.seats[seat].folded = true;
+        } else {
+            self.commit(seat, target - state.street_bet);
+        }
+
+        let street_bet = self.seats[seat].street_bet;
+        if street_bet > self.current_bet {
+            // Only a full raise reopens the betting for players who acted already, a smaller
+            // all-in just has to be called.
+            let increment = street_bet - self.current_bet;
+            if increment >= self.min_raise {
+                self.min_raise = increment;
+                self.bets += 1;
+                for other in self.seats.iter_mut() {
+                    other.acted = false;
+                }
+            }
+            self.current_bet = street_bet;
+        }
+
+        self.seats[seat].acted = true;
+        self.seats[seat].matched = self.current_bet;
+        self.game_history.push(GameEvent::Action { seat, action });
+        self.advance()
+    }
+
+    /// What the player whose turn it is may do, nothing when no hand is in progress.
+    ///
+    /// The sizes of bets and raises follow the [BettingStructure], a bet is at least one bet of
+    /// the street and a raise at least the last bet or raise increment. Going all-in for less is
+    /// always allowed, going all-in for more only when the structure allows that total.
+    pub fn legal_actions(&self) -> LegalActions {
+        let Some(seat) = self.to_act else {
+            return LegalActions::default();
+        };
+
+        let state = &self.seats[seat];
+        let max_total = state.street_bet + self.players[seat].stack();
+        let opponents = (0..self.seats.len())
+            .filter(|other| *other != seat)
+            .any(|other| self.seats[other].can_act());
+
+        // Several small all-ins together reopen the betting once they add up to a full raise.
+        let reopened = !state.acted || self.current_bet >= state.matched + self.min_raise;
+        let to_call = self.current_bet - state.street_bet;
+        let limits = self.config.betting.limits(&BettingRound {
+            street: self.street.unwrap_or(Street::Preflop),
+            big_blind: self.config.big_blind,
+            current_bet: self.current_bet,
+            min_raise: self.min_raise,
+            to_call,
+            pot: self.pot(),
+            bets: self.bets,
+            players_in_hand: self.seats.iter().filter(|seat| seat.in_hand()).count(),
+        });
+        let limits = limits.filter(|_| reopened && opponents && max_total > self.current_bet);
+
+        let range = limits.map(|(min, max)| (min.min(max_total), max.min(max_total)));
+        LegalActions {
+            to_call,
+            reopened,
+            bet: range.filter(|_| self.current_bet == 0),
+            raise: range.filter(|_| self.current_bet > 0),
+            all_in: (max_total > state.street_bet
+                && (max_total <= self.current_bet
+                    || limits.is_some_and(|(_, max)| max_total <= max)))
+            .then_some(max_total),
+        }
+    }
+
+    /// What the seat whose turn it is can see, `None` when no hand is in progress.
+    pub fn observe(&self) -> Option<Observation> {
+        let seat = self.to_act?;
+        let history = self.game_history[self.hand_start..]
+            .iter()
+            .filter(|event| !matches!(event, GameEvent::HoleCards { seat: other, .. } if *other != seat))
+            .cloned()
+            .collect();
+
+        Some(Observation {
+            seat,
+            hole_cards: self.seats[seat].hole_cards,
+            board: self.board,
+            street: self.street?,
+            dealer: self.dealer,
+            big_blind: self.config.big_blind,
+            pot: self.pot(),
+            current_bet: self.current_bet,
+            stacks: self.players.iter().map(Player::stack).collect(),
+            street_bets: self.seats.iter().map(|seat| seat.street_bet).collect(),
+            in_hand: self.seats.iter().map(Seat::in_hand).collect(),
+            history,
+            legal_actions: self.legal_actions(),
+        })
+    }
+
+    /// Start the next hand and play it out with one [Agent] per seat.
+    pub fn play_hand(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<(), GameError> {
+        self.new_round()?;
+        self.finish_hand(agents)
+    }
+
+    /// Let the agent of the seat whose turn it is act until the hand is over.
+    pub fn finish_hand(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<(), GameError> {
+        while let Some(observation) = self.observe() {
+            let seat = observation.seat();
+            let agent = agents.get_mut(seat).ok_or(GameError::MissingAgent(seat))?;
+            let action = agent.act(&observation);
+            self.act(seat, action)?;
+        }
+        Ok(())
+    }
+
+    /// Pass the turn on, or close the round and deal the next street, or finish the hand.
+    fn advance(&mut self) -> Result<(), GameError> {
+        let in_hand = self.seats.iter().filter(|seat| seat.in_hand()).count();
+        if in_hand == 1 {
+            self.return_uncalled_bet();
+            let winner = self.seats.iter().position(Seat::in_hand).unwrap();
+            let pot = self.pot();
+            self.players[winner].give(pot);
+            self.game_history.push(GameEvent::Win {
+                seat: winner,
+                amount: pot,
+            });
+            self.end_hand();
+            return Ok(());
+        }
+
+        let current_bet = self.current_bet;
+        let pending =
+            |seat: &Seat| seat.can_act() && (!seat.acted || seat.street_bet < current_bet);
+
+        // A lone player who matched the bet has nobody left to bet against.
+        let mut actors = self.seats.iter().filter(|seat| seat.can_act());
+        let alone = match (actors.next(), actors.next()) {
+            (Some(seat), None) => seat.street_bet >= current_bet,
+            (None, _) => true,
+            _ => false,
+        };
+
+        if !alone && self.seats.iter().any(pending) {
+            self.to_act = self.next_seat(self.to_act.unwrap_or(self.dealer), pending);
+            return Ok(());
+        }
+
+        // Once at most one player can still bet, the rest of the board is dealt without betting.
+        loop {
+            let Some(street) = self.next_street()? else {
+                return self.showdown();
+            };
+            self.street = Some(street);
+
+            if self.seats.iter().filter(|seat| seat.can_act()).count() > 1 {
+                self.to_act = self.next_seat(self.dealer, Seat::can_act);
+                return Ok(());
+            }
+        }
+    }
+
+    /// Deal the board of the street after the current one, `None` after the river.
+    fn next_street(&mut self) -> Result<Option<Street>, GameError> {
+        for seat in self.seats.iter_mut() {
+            seat.street_bet = 0;
+            seat.acted = false;
+        }
+        self.current_bet = 0;
+        self.bets = 0;
+
+        let (street, count) = match self.street {
+            Some(Street::Preflop) => (Street::Flop, 3),
+            Some(Street::Flop) => (Street::Turn, 1),
+            Some(Street::Turn) => (Street::River, 1),
+            _ => return Ok(None),
+        };
+        self.min_raise = self.config.betting.bet_size(street, self.config.big_blind);
+
+        self.deck.burn()?;
+        let cards = self.deck.deal(count)?;
+        self.board |= cards;
+        self.game_history.push(match street {
+            Street::Flop => GameEvent::Flop(cards),
+            Street::Turn => GameEvent::Turn(cards),
+            _ => GameEvent::River(cards),
+        });
+
+        Ok(Some(street))
+    }
+
+    /// Give back the part of the largest contribution that nobody matched, before the pot is
+    /// awarded.
+    fn return_uncalled_bet(&mut self) {
+        let mut contributions: Vec<u32> = self.seats.iter().map(|seat| seat.contributed).collect();
+        contributions.sort_unstable_by(|a, b| b.cmp(a));
+        let uncalled = contributions[0] - contributions[1];
+        if uncalled > 0 {
+            let seat = self
+                .seats
+                .iter()
+                .position(|seat| seat.contributed == contributions[0])
+                .unwrap();
+            self.seats[seat].contributed -= uncalled;
+            self.players[seat].give(uncalled);
+            self.game_history.push(GameEvent::UncalledBet {
+                seat,
+                amount: uncalled,
+            });
+        }
+    }
+
+    fn showdown(&mut self) -> Result<(), GameError> {
+        // A bet nobody could match in full goes back before the pot is split.
+        self.return_uncalled_bet();
+
+        // Only hands that can win chips from someone else are shown.
+        let pots = self.pots();
+        let mut outcomes = vec![None; self.seats.len()];
+        for (seat, outcome) in outcomes.iter_mut().enumerate() {
+            let contested = pots
+                .iter()
+                .any(|pot| pot.eligible().len() > 1 && pot.eligible().contains(&seat));
+            if contested {
+                let shown = Outcome::try_from(self.seats[seat].hole_cards | self.board)?;
+                self.game_history.push(GameEvent::Showdown {
+                    seat,
+                    outcome: shown,
+                });
+                *outcome = Some(shown);
+            }
+        }
+
+        let hole_cards: Vec<Cards> = self.seats.iter().map(|seat| seat.hole_cards).collect();
+        let order = self.config.odd_chip.order(self.dealer, &hole_cards);
+        for pot in pots {
+            let payouts = distribute(std::slice::from_ref(&pot), &outcomes, &order);
+            for (seat, amount) in payouts.into_iter().enumerate() {
+                if amount > 0 {
+                    self.players[seat].give(amount);
+                    self.game_history.push(GameEvent::Win { seat, amount });
+                }
+            }
+        }
+
+        self.end_hand();
+        Ok(())
+    }
+
+    fn end_hand(&mut self) {
+        for seat in self.seats.iter_mut() {
+            seat.street_bet = 0;
+            seat.contributed = 0;
+        }
+        self.street = None;
+        self.to_act = None;
+        self.hands_played += 1;
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    pub fn add_player(&mut self, player: Player) {
         self.players.push(player);
+        self.sitting_out.push(false);
+        self.missed_blinds.push(MissedBlinds::default());
+    }
+
+    /// Leave `seat` out of the next hands, it misses the blinds that pass it meanwhile.
+    pub fn sit_out(&mut self, seat: usize) -> Result<(), GameError> {
+        *self
+            .sitting_out
+            .get_mut(seat)
+            .ok_or(GameError::InvalidSeat(seat))? = true;
+        Ok(())
+    }
+
+    /// Deal `seat` back in from the next hand, posting the blinds it missed.
+    pub fn sit_in(&mut self, seat: usize) -> Result<(), GameError> {
+        *self
+            .sitting_out
+            .get_mut(seat)
+            .ok_or(GameError::InvalidSeat(seat))? = false;
+        Ok(())
+    }
+
+    pub fn is_sitting_out(&self, seat: usize) -> bool {
+        self.sitting_out.get(seat).copied().unwrap_or_default()
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// The street being played, `None` between hands.
+    pub fn street(&self) -> Option<Street> {
+        self.street
+    }
+
+    /// The seat whose turn it is, `None` between hands.
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn board(&self) -> Cards {
+        self.board
+    }
+
+    /// The hole cards of `seat` in the current or last hand.
+    pub fn hole_cards(&self, seat: usize) -> Cards {
+        self.seats
+            .get(seat)
+            .map(|seat| seat.hole_cards)
+            .unwrap_or_default()
+    }
+
+    /// The main pot and side pots of the current hand, see [build_pots].
+    pub fn pots(&self) -> Vec<Pot> {
+        let contributions: Vec<u32> = self.seats.iter().map(|seat| seat.contributed).collect();
+        let in_hand: Vec<bool> = self.seats.iter().map(Seat::in_hand).collect();
+        build_pots(&contributions, &in_hand)
+    }
+
+    /// All chips put in during the current hand, 0 once it is paid out.
+    pub fn pot(&self) -> u32 {
+        self.seats.iter().map(|seat| seat.contributed).sum()
     }
 
-    pub fn add_bet(&mut self, bet: u32) -> () {
-        self.game_history.push(GameEvent::Bet(bet));
+    /// The amount every player has to match on this street.
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    /// The chips `seat` put in on this street.
+    pub fn street_bet(&self, seat: usize) -> u32 {
+        self.seats.get(seat).map_or(0, |seat| seat.street_bet)
+    }
+
+    /// Whether `seat` still plays for the pot of the current hand.
+    pub fn in_hand(&self, seat: usize) -> bool {
+        self.seats.get(seat).is_some_and(Seat::in_hand)
+    }
 
+    pub fn history(&self) -> &[GameEvent] {
+        &self.game_history
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GameConfig {
+        GameConfig {
+            small_blind: 5,
+            big_blind: 10,
+            seed: 7,
+            ..GameConfig::default()
+        }
+    }
+
+    fn game(stacks: &[u32]) -> Game {
+        game_with(stacks, config())
+    }
+
+    fn game_with(stacks: &[u32], config: GameConfig) -> Game {
+        let mut game = Game::new(config);
+        for (i, stack) in stacks.iter().enumerate() {
+            game.add_player(Player::new(format!("player {}", i), *stack));
+        }
+        game
+    }
+
+    fn play(game: &mut Game, action: Action) -> Result<(), GameError> {
+        game.act(game.to_act().unwrap_or_default(), action)
+    }
+
+    fn chips(game: &Game) -> u32 {
+        game.players().iter().map(Player::stack).sum::<u32>() + game.pot()
+    }
+
+    #[test]
+    fn test_blinds_and_order() {
+        let mut game = game(&[1000, 1000, 1000]);
+        game.new_round().unwrap();
+
+        // Button 0, blinds 1 and 2, so the button acts first before the flop.
+        assert_eq!(Some(Street::Preflop), game.street());
+        assert_eq!(Some(0), game.to_act());
+        assert_eq!((5, 10), (game.street_bet(1), game.street_bet(2)));
+        assert_eq!(15, game.pot());
+        for seat in 0..3 {
+            assert_eq!(2, game.hole_cards(seat).card_count());
+        }
+
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(2), game.to_act());
+        assert_eq!(
+            vec![
+                Action::Fold,
+                Action::Check,
+                Action::Raise(20),
+                Action::AllIn
+            ],
+            game.legal_actions().actions()
+        );
+
+        play(&mut game, Action::Check).unwrap();
+        assert_eq!(Some(Street::Flop), game.street());
+        assert_eq!(3, game.board().card_count());
+        assert_eq!(Some(1), game.to_act());
+    }
+
+    #[test]
+    fn test_heads_up_blinds() {
+        let mut game = game(&[1000, 1000]);
+        game.new_round().unwrap();
+
+        assert_eq!(0, game.dealer());
+        assert_eq!((5, 10), (game.street_bet(0), game.street_bet(1)));
+        assert_eq!(Some(0), game.to_act());
+
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Check).unwrap();
+        assert_eq!(Some(1), game.to_act());
+    }
+
+    #[test]
+    fn test_fold_wins_pot() {
+        let mut game = game(&[1000, 1000, 1000]);
+        game.new_round().unwrap();
+
+        play(&mut game, Action::Raise(30)).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+
+        assert_eq!(None, game.street());
+        assert_eq!(
+            [1015, 995, 990],
+            [0, 1, 2].map(|seat| game.players()[seat].stack())
+        );
+        // The raise of 30 was only called up to the big blind of 10.
+        assert!(matches!(
+            game.history()[game.history().len() - 2..],
+            [
+                GameEvent::UncalledBet {
+                    seat: 0,
+                    amount: 20
+                },
+                GameEvent::Win {
+                    seat: 0,
+                    amount: 25
+                }
+            ]
+        ));
+
+        game.new_round().unwrap();
+        assert_eq!(1, game.dealer());
+    }
+
+    #[test]
+    fn test_fold_to_bet_returns_it() {
+        let mut game = game(&[1000, 1000, 1000]);
+        game.new_round().unwrap();
+
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Check).unwrap();
+        assert_eq!(Some(Street::Flop), game.street());
+
+        play(&mut game, Action::Bet(100)).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+
+        assert_eq!(None, game.street());
+        assert_eq!(
+            [1020, 990, 990],
+            [1, 2, 0].map(|seat| game.players()[seat].stack())
+        );
+        assert!(matches!(
+            game.history()[game.history().len() - 2..],
+            [
+                GameEvent::UncalledBet {
+                    seat: 1,
+                    amount: 100
+                },
+                GameEvent::Win {
+                    seat: 1,
+                    amount: 30
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_check_down_to_showdown() {
+        let mut game = game(&[1000, 1000, 1000]);
+        game.new_round().unwrap();
+
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Check).unwrap();
+        while game.street().is_some() {
+            play(&mut game, Action::Check).unwrap();
+        }
+
+        assert_eq!(5, game.board().card_count());
+        assert_eq!(3000, chips(&game));
+        let showdowns = game
+            .history()
+            .iter()
+            .filter(|event| matches!(event, GameEvent::Showdown { .. }))
+            .count();
+        assert_eq!(3, showdowns);
+    }
+
+    #[test]
+    fn test_all_in_runs_out_board() {
+        let mut game = game(&[1000, 300]);
+        game.new_round().unwrap();
+
+        play(&mut game, Action::AllIn).unwrap();
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(None, game.street());
+        assert_eq!(5, game.board().card_count());
+        assert_eq!(1300, chips(&game));
+        assert!(game.history().iter().any(|event| matches!(
+            event,
+            GameEvent::UncalledBet {
+                seat: 0,
+                amount: 700
+            }
+        )));
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let mut game = game(&[1000, 200, 500]);
+        game.new_round().unwrap();
+
+        play(&mut game, Action::AllIn).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(1210, game.pots().iter().map(Pot::amount).sum::<u32>());
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(None, game.street());
+        assert_eq!(1700, chips(&game));
+        assert!(game.players()[1].stack() <= 600);
+
+        let won: u32 = game
+            .history()
+            .iter()
+            .map(|event| match event {
+                GameEvent::Win { amount, .. } => *amount,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(1200, won);
+        assert!(game.history().iter().any(|event| matches!(
+            event,
+            GameEvent::UncalledBet {
+                seat: 0,
+                amount: 500
+            }
+        )));
+    }
+
+    #[test]
+    fn test_illegal_actions() {
+        let mut game = game(&[1000, 1000, 1000]);
+        assert_eq!(
+            Err(GameError::NoHandInProgress),
+            play(&mut game, Action::Check)
+        );
+        game.new_round().unwrap();
+
+        assert_eq!(Err(GameError::HandInProgress), game.new_round());
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::OutOfTurn {
+                seat: 1,
+                to_act: 0
+            })),
+            game.act(1, Action::Call)
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::CannotCheck {
+                to_call: 10
+            })),
+            play(&mut game, Action::Check)
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::BetNotAllowed)),
+            play(&mut game, Action::Bet(20))
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseOutOfRange {
+                amount: 15,
+                min: 20,
+                max: 1000
+            })),
+            play(&mut game, Action::Raise(15))
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseOutOfRange {
+                amount: 1001,
+                min: 20,
+                max: 1000
+            })),
+            play(&mut game, Action::Raise(1001))
+        );
+
+        let mut empty = Game::default();
+        empty.add_player(Player::new("alone".to_string(), 100));
+        assert_eq!(Err(GameError::NotEnoughPlayers), empty.new_round());
+    }
+
+    #[test]
+    fn test_min_bet_and_raise() {
+        let mut game = game(&[1000, 1000, 1000]);
+        game.new_round().unwrap();
+
+        // A raise to 50 is an increment of 40, so the next raise goes to at least 90.
+        play(&mut game, Action::Raise(50)).unwrap();
+        assert_eq!(Some((90, 1000)), game.legal_actions().raise_range());
+        assert_eq!(45, game.legal_actions().to_call());
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(Some(Street::Flop), game.street());
+        assert_eq!(Some((10, 950)), game.legal_actions().bet_range());
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::BetOutOfRange {
+                amount: 5,
+                min: 10,
+                max: 950
+            })),
+            play(&mut game, Action::Bet(5))
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseNotAllowed)),
+            play(&mut game, Action::Raise(20))
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::NothingToCall)),
+            play(&mut game, Action::Call)
+        );
+        play(&mut game, Action::Bet(10)).unwrap();
+        assert_eq!(Some((20, 950)), game.legal_actions().raise_range());
+    }
+
+    #[test]
+    fn test_incomplete_all_in_does_not_reopen() {
+        let mut game = game(&[1000, 1000, 1000, 1000, 35]);
+        game.new_round().unwrap();
+
+        // Button 0, blinds 1 and 2, seat 3 raises a full 20 and seat 4 adds only 5 more.
+        play(&mut game, Action::Raise(30)).unwrap();
+        play(&mut game, Action::AllIn).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+
+        // The big blind had not acted yet, so it may still raise.
+        assert_eq!(Some(2), game.to_act());
+        assert_eq!(Some((55, 1000)), game.legal_actions().raise_range());
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(Some(3), game.to_act());
+        let legal = game.legal_actions();
+        assert_eq!(5, legal.to_call());
+        assert_eq!(None, legal.raise_range());
+        assert_eq!(None, legal.all_in());
+        assert_eq!(vec![Action::Fold, Action::Call], legal.actions());
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseNotReopened)),
+            play(&mut game, Action::Raise(60))
+        );
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseNotReopened)),
+            play(&mut game, Action::AllIn)
+        );
+
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(Street::Flop), game.street());
+    }
+
+    #[test]
+    fn test_all_in_for_less_than_min() {
+        let mut game = game(&[1000, 1000, 25]);
+        game.new_round().unwrap();
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Check).unwrap();
+
+        // Seat 2 has 15 behind, so it may raise the bet of 10 all-in to 15 but not further.
+        play(&mut game, Action::Bet(10)).unwrap();
+        assert_eq!(Some(2), game.to_act());
+        assert_eq!(Some((15, 15)), game.legal_actions().raise_range());
+        play(&mut game, Action::Raise(15)).unwrap();
+
+        // The button has not acted yet and may raise a full increment over the all-in.
+        assert_eq!(Some((25, 990)), game.legal_actions().raise_range());
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(Some(1), game.to_act());
+        assert_eq!(None, game.legal_actions().raise_range());
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(Street::Turn), game.street());
+    }
+
+    #[test]
+    fn test_pot_limit() {
+        let mut game = game_with(
+            &[1000, 1000, 1000],
+            GameConfig {
+                betting: BettingStructure::PotLimit,
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        // Calling 10 makes a pot of 25, so the button may raise to at most 35.
+        assert_eq!(Some((20, 35)), game.legal_actions().raise_range());
+        assert_eq!(None, game.legal_actions().all_in());
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseOutOfRange {
+                amount: 40,
+                min: 20,
+                max: 35
+            })),
+            play(&mut game, Action::Raise(40))
+        );
+        play(&mut game, Action::Raise(35)).unwrap();
+
+        // The small blind calls 30 into 50, raising the pot of 80 on top of the 35.
+        assert_eq!(Some((60, 115)), game.legal_actions().raise_range());
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some((60, 140)), game.legal_actions().raise_range());
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(Some(Street::Flop), game.street());
+        assert_eq!(Some((10, 105)), game.legal_actions().bet_range());
+    }
+
+    #[test]
+    fn test_fixed_limit() {
+        let betting = BettingStructure::FixedLimit {
+            small_bet: 10,
+            big_bet: 20,
+            raise_cap: 4,
+        };
+        let mut game = game_with(
+            &[1000, 1000, 1000],
+            GameConfig {
+                betting,
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        assert!(matches!(
+            game.history()[0],
+            GameEvent::NewHand { betting: recorded, .. } if recorded == betting
+        ));
+
+        // The big blind is the first bet, three raises reach the cap of 4.
+        for total in [20, 30, 40] {
+            assert_eq!(Some((total, total)), game.legal_actions().raise_range());
+            play(&mut game, Action::Raise(total)).unwrap();
+        }
+        assert_eq!(None, game.legal_actions().raise_range());
+        assert_eq!(None, game.legal_actions().all_in());
+        assert_eq!(
+            Err(GameError::IllegalAction(ActionError::RaiseNotAllowed)),
+            play(&mut game, Action::Raise(50))
+        );
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+
+        assert_eq!(Some(Street::Flop), game.street());
+        assert_eq!(Some((10, 10)), game.legal_actions().bet_range());
+        for _ in 0..3 {
+            play(&mut game, Action::Check).unwrap();
+        }
+        assert_eq!(Some(Street::Turn), game.street());
+        assert_eq!(Some((20, 20)), game.legal_actions().bet_range());
+    }
+
+    #[test]
+    fn test_fixed_limit_heads_up_is_not_capped() {
+        let betting = BettingStructure::FixedLimit {
+            small_bet: 10,
+            big_bet: 20,
+            raise_cap: 4,
+        };
+        let mut game = game_with(
+            &[1000, 1000],
+            GameConfig {
+                betting,
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        for total in [20, 30, 40, 50, 60] {
+            assert_eq!(Some((total, total)), game.legal_actions().raise_range());
+            play(&mut game, Action::Raise(total)).unwrap();
+        }
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(Street::Flop), game.street());
+        assert_eq!(120, game.pot());
+    }
+
+    fn events(
+        game: &Game,
+        filter: impl Fn(&GameEvent) -> Option<(usize, u32)>,
+    ) -> Vec<(usize, u32)> {
+        game.history().iter().filter_map(filter).collect()
+    }
+
+    #[test]
+    fn test_antes() {
+        let mut game = game_with(
+            &[1000, 1000, 1000],
+            GameConfig {
+                ante: 2,
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        let antes = |event: &GameEvent| match event {
+            GameEvent::Ante { seat, amount } => Some((*seat, *amount)),
+            _ => None,
+        };
+        assert_eq!(vec![(1, 2), (2, 2), (0, 2)], events(&game, antes));
+        assert_eq!(21, game.pot());
+
+        // Antes are dead, the big blind still only has to be called.
+        assert_eq!(10, game.legal_actions().to_call());
+        assert_eq!(Some((20, 998)), game.legal_actions().raise_range());
+        play(&mut game, Action::Fold).unwrap();
+        play(&mut game, Action::Fold).unwrap();
+        assert_eq!(1009, game.players()[2].stack());
+    }
+
+    #[test]
+    fn test_big_blind_ante() {
+        let mut game = game_with(
+            &[1000, 1000, 1000],
+            GameConfig {
+                big_blind_ante: 10,
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        assert!(matches!(
+            game.history()[3],
+            GameEvent::Ante {
+                seat: 2,
+                amount: 10
+            }
+        ));
+        assert_eq!(25, game.pot());
+        assert_eq!(10, game.street_bet(2));
+        assert_eq!(980, game.players()[2].stack());
+
+        play(&mut game, Action::Call).unwrap();
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(2), game.to_act());
+        assert!(game.legal_actions().can_check());
+    }
+
+    #[test]
+    fn test_straddles() {
+        let mut game = game_with(
+            &[1000, 1000, 1000, 1000],
+            GameConfig {
+                straddle: Some(Straddle::UnderTheGun),
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+
+        // Seat 3 straddles, the button acts first and has to raise to at least 40.
+        assert_eq!(20, game.street_bet(3));
+        assert_eq!(Some(0), game.to_act());
+        assert_eq!(20, game.legal_actions().to_call());
+        assert_eq!(Some((40, 1000)), game.legal_actions().raise_range());
+        for _ in 0..3 {
+            play(&mut game, Action::Call).unwrap();
+        }
+
+        // The straddle gets the option last.
+        assert_eq!(Some(3), game.to_act());
+        assert!(game.legal_actions().can_check());
+        play(&mut game, Action::Check).unwrap();
+        assert_eq!(Some(Street::Flop), game.street());
+
+        let mut game = game_with(
+            &[1000, 1000, 1000],
+            GameConfig {
+                straddle: Some(Straddle::Button),
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+        assert!(matches!(
+            game.history()[3],
+            GameEvent::Straddle {
+                seat: 0,
+                amount: 20
+            }
+        ));
+        assert_eq!(Some(1), game.to_act());
+
+        // Heads-up nobody straddles.
+        let mut game = game_with(
+            &[1000, 1000],
+            GameConfig {
+                straddle: Some(Straddle::Button),
+                ..config()
+            },
+        );
+        game.new_round().unwrap();
+        assert_eq!(10, game.current_bet());
+    }
+
+    #[test]
+    fn test_missed_blinds() {
+        let mut game = game(&[1000, 1000, 1000, 1000]);
+        game.sit_out(3).unwrap();
+        assert_eq!(Err(GameError::InvalidSeat(4)), game.sit_out(4));
+
+        // The blinds go from seats 1 and 2 to 2 and 0 and then to 0 and 1, passing seat 3 by.
+        for _ in 0..3 {
+            game.new_round().unwrap();
+            assert!(!game.in_hand(3));
+            play(&mut game, Action::Fold).unwrap();
+            play(&mut game, Action::Fold).unwrap();
+        }
+        let missed = |event: &GameEvent| match event {
+            GameEvent::MissedBlind { seat, amount } => Some((*seat, *amount)),
+            _ => None,
+        };
+        assert_eq!(vec![(3, 10), (3, 5)], events(&game, missed));
+
+        game.sit_in(3).unwrap();
+        let start = game.history().len();
+        game.new_round().unwrap();
+
+        // Button 3, blinds 0 and 1, seat 3 posts a live big blind and a dead small blind.
+        let posted: Vec<String> = game.history()[start..]
+            .iter()
+            .filter(|event| matches!(event, GameEvent::Blind { .. } | GameEvent::DeadBlind { .. }))
+            .map(|event| format!("{:?}", event))
+            .collect();
+        assert_eq!(
+            vec![
+                "Blind { seat: 0, amount: 5 }",
+                "Blind { seat: 1, amount: 10 }",
+                "Blind { seat: 3, amount: 10 }",
+                "DeadBlind { seat: 3, amount: 5 }",
+            ],
+            posted
+        );
+        assert_eq!(30, game.pot());
+        assert_eq!(10, game.street_bet(3));
+
+        // The big blind it posted is live, so the returning player can check when it gets called.
+        play(&mut game, Action::Call).unwrap();
+        assert_eq!(Some(3), game.to_act());
+        assert!(game.legal_actions().can_check());
+    }
+
+    #[test]
+    fn test_observation() {
+        let mut game = game(&[1000, 1000, 1000]);
+        assert!(game.observe().is_none());
+        game.new_round().unwrap();
+
+        let observation = game.observe().unwrap();
+        assert_eq!(0, observation.seat());
+        assert_eq!(game.hole_cards(0), observation.hole_cards());
+        assert_eq!(&game.legal_actions(), observation.legal_actions());
+        assert_eq!(&[1000, 995, 990], observation.stacks());
+        assert_eq!(15, observation.pot());
+
+        // Only its own hole cards are in the history the seat sees.
+        let hole_cards: Vec<usize> = observation
+            .history()
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::HoleCards { seat, .. } => Some(*seat),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![0], hole_cards);
+    }
+}
@@ -8,4 +8,23 @@ impl Player {
     pub fn new(name: String, stack: u32) -> Self {
         Self { name, stack }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn stack(&self) -> u32 {
+        self.stack
+    }
+
+    /// Take up to `amount` chips from the stack, returns the chips taken.
+    pub(crate) fn take(&mut self, amount: u32) -> u32 {
+        let taken = amount.min(self.stack);
+        self.stack -= taken;
+        taken
+    }
+
+    pub(crate) fn give(&mut self, amount: u32) {
+        self.stack += amount;
+    }
 }
@@ -0,0 +1,248 @@
+use crate::engine::cards::Cards;
+use crate::engine::outcome::Outcome;
+
+/// A main or side pot and the seats that can win it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pot {
+    amount: u32,
+    eligible: Vec<usize>,
+}
+
+impl Pot {
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    /// The seats still in the hand that put in enough to win this pot, in seat order.
+    pub fn eligible(&self) -> &[usize] {
+        &self.eligible
+    }
+}
+
+/// Who gets the chips that cannot be split evenly between the winners of a pot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OddChipRule {
+    /// The winner closest to the left of the button gets the first odd chip.
+    #[default]
+    LeftOfButton,
+    /// The winner with the highest hole card, by rank and then suit, gets the first odd chip.
+    HighestCard,
+}
+
+impl OddChipRule {
+    /// Every seat in the order it receives odd chips.
+    pub fn order(&self, dealer: usize, hole_cards: &[Cards]) -> Vec<usize> {
+        let seats = hole_cards.len();
+        let mut order: Vec<usize> = (1..=seats).map(|i| (dealer + i) % seats).collect();
+
+        if *self == OddChipRule::HighestCard {
+            order.sort_by_key(|seat| std::cmp::Reverse(hole_cards[*seat].iter().next()));
+        }
+        order
+    }
+}
+
+/// Split the chips put in by every seat into a main pot and side pots.
+///
+/// Each pot is capped at the contribution of a player still in the hand, so an all-in player is
+/// only eligible for the pots they covered. Chips of folded players count towards the pots but
+/// make them eligible for nothing. A pot with a single eligible seat holds a bet nobody called.
+pub fn build_pots(contributions: &[u32], in_hand: &[bool]) -> Vec<Pot> {
+    let mut levels: Vec<u32> = contributions
+        .iter()
+        .zip(in_hand)
+        .filter(|(contribution, in_hand)| **in_hand && **contribution > 0)
+        .map(|(contribution, _)| *contribution)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = vec![];
+    let mut previous = 0;
+    for level in levels {
+        let amount = contributions
+            .iter()
+            .map(|contribution| (*contribution).min(level) - (*contribution).min(previous))
+            .sum();
+        let eligible = (0..contributions.len())
+            .filter(|seat| in_hand[*seat] && contributions[*seat] >= level)
+            .collect();
+
+        pots.push(Pot { amount, eligible });
+        previous = level;
+    }
+
+    // Folded players can have put in more than anyone left, those chips go to the last pot.
+    let rest: u32 = contributions
+        .iter()
+        .map(|contribution| contribution.saturating_sub(previous))
+        .sum();
+    if pots.is_empty() && rest > 0 {
+        pots.push(Pot {
+            amount: 0,
+            eligible: (0..in_hand.len()).filter(|seat| in_hand[*seat]).collect(),
+        });
+    }
+    if let Some(last) = pots.last_mut() {
+        last.amount += rest;
+    }
+
+    pots
+}
+
+/// The chips every seat wins from `pots`.
+///
+/// Each pot goes to its eligible seats with the best [Outcome], split evenly on ties with the odd
+/// chips handed out one by one in `odd_chip_order`. A seat without an outcome only wins when no
+/// eligible seat has one, like the only seat left in a pot. Every chip of every pot is paid out.
+pub fn distribute(
+    pots: &[Pot],
+    outcomes: &[Option<Outcome>],
+    odd_chip_order: &[usize],
+) -> Vec<u32> {
+    let mut payouts = vec![0; outcomes.len()];
+
+    for pot in pots {
+        let best = pot.eligible.iter().filter_map(|seat| outcomes[*seat]).max();
+
+        let mut winners: Vec<usize> = pot
+            .eligible
+            .iter()
+            .copied()
+            .filter(|seat| match (outcomes[*seat], best) {
+                (Some(outcome), Some(best)) => outcome.cmp(&best).is_eq(),
+                (_, None) => true,
+                (None, Some(_)) => false,
+            })
+            .collect();
+        winners.sort_by_key(|seat| odd_chip_order.iter().position(|other| other == seat));
+
+        let share = pot.amount / winners.len() as u32;
+        let odd_chips = (pot.amount % winners.len() as u32) as usize;
+        for (i, seat) in winners.into_iter().enumerate() {
+            payouts[seat] += share + (i < odd_chips) as u32;
+        }
+    }
+
+    payouts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::rng::{Rng, Xoshiro256};
+
+    use super::*;
+
+    fn outcome(cards: &str) -> Option<Outcome> {
+        Some(cards.parse::<Cards>().unwrap().try_into().unwrap())
+    }
+
+    #[test]
+    fn test_side_pots() {
+        // Seat 1 is all-in for 50, seat 3 folded after putting in 20.
+        let pots = build_pots(&[100, 50, 100, 20], &[true, true, true, false]);
+
+        assert_eq!(2, pots.len());
+        assert_eq!(
+            (170, vec![0, 1, 2]),
+            (pots[0].amount(), pots[0].eligible().to_vec())
+        );
+        assert_eq!(
+            (100, vec![0, 2]),
+            (pots[1].amount(), pots[1].eligible().to_vec())
+        );
+    }
+
+    #[test]
+    fn test_uncalled_bet() {
+        let pots = build_pots(&[300, 100], &[true, true]);
+
+        assert_eq!(
+            vec![1, 0],
+            pots.iter()
+                .map(|pot| pot.eligible().len() - 1)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![200, 0],
+            distribute(&pots[1..], &[None, outcome("AsAhAdAc2s")], &[0, 1])
+        );
+    }
+
+    #[test]
+    fn test_distribute() {
+        let pots = build_pots(&[100, 50, 100, 20], &[true, true, true, false]);
+        let outcomes = [
+            outcome("As Ks Qs Js 9s"),
+            outcome("Ah Kh Qh Jh Th"),
+            outcome("Ad Kd Qd Jd 9d"),
+            None,
+        ];
+
+        // The short stack wins the main pot, the other two split the side pot.
+        assert_eq!(
+            vec![50, 170, 50, 0],
+            distribute(&pots, &outcomes, &[0, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_odd_chips() {
+        let pots = build_pots(&[35, 35, 35], &[true, true, false]);
+        let outcomes = [outcome("AsKsQsJs9s"), outcome("AhKhQhJh9h"), None];
+
+        let left_of_button = OddChipRule::LeftOfButton.order(1, &[Cards::default(); 3]);
+        assert_eq!(vec![2, 0, 1], left_of_button);
+        assert_eq!(
+            vec![53, 52, 0],
+            distribute(&pots, &outcomes, &left_of_button)
+        );
+
+        let hole_cards = ["2c3c", "2d3d", "4s5s"].map(|cards| cards.parse().unwrap());
+        let highest_card = OddChipRule::HighestCard.order(0, &hole_cards);
+        assert_eq!(vec![2, 1, 0], highest_card);
+        assert_eq!(vec![52, 53, 0], distribute(&pots, &outcomes, &highest_card));
+    }
+
+    #[test]
+    fn test_chips_are_conserved() {
+        let mut rng = Xoshiro256::from_seed(11);
+        let hands = [
+            outcome("As Ks Qs Js 9h"),
+            outcome("Ah Kh Qh Jh 9h"),
+            outcome("2c 2d 5h 7s 9d"),
+            outcome("3c 3d 5h 7s 9d"),
+        ];
+
+        for _ in 0..1000 {
+            let players = 2 + rng.below(5) as usize;
+            let contributions: Vec<u32> = (0..players).map(|_| rng.below(200) as u32).collect();
+            let mut in_hand: Vec<bool> = (0..players).map(|_| rng.below(3) != 0).collect();
+            in_hand[rng.below(players as u64) as usize] = true;
+
+            let outcomes: Vec<Option<Outcome>> = (0..players)
+                .map(|seat| {
+                    in_hand[seat]
+                        .then(|| hands[rng.below(4) as usize])
+                        .flatten()
+                })
+                .collect();
+            let order: Vec<usize> = (0..players).collect();
+
+            let pots = build_pots(&contributions, &in_hand);
+            let payouts = distribute(&pots, &outcomes, &order);
+
+            let total: u32 = contributions.iter().sum();
+            assert_eq!(
+                total,
+                pots.iter().map(Pot::amount).sum::<u32>(),
+                "{:?}",
+                contributions
+            );
+            assert_eq!(total, payouts.iter().sum::<u32>(), "{:?}", contributions);
+            for (seat, payout) in payouts.iter().enumerate() {
+                assert!(in_hand[seat] || *payout == 0);
+            }
+        }
+    }
+}
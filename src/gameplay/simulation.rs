@@ -0,0 +1,270 @@
+use std::time::{Duration, Instant};
+
+use crate::util::rng::{Rng, Xoshiro256};
+
+use super::agent::Agent;
+use super::game::{Game, GameConfig, GameError, GameEvent};
+use super::player::Player;
+
+/// The table and the length of a [simulate] run.
+///
+/// The same seed and hand count always give the same result for agents that play the same given
+/// the same observations, a time budget does not since the amount of hands depends on the machine.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// The stakes and rules of every hand, its seed is replaced by one per deal.
+    pub game: GameConfig,
+    /// Every hand starts with every stack at this amount.
+    pub starting_stack: u32,
+    pub hands: u64,
+    pub time_budget: Option<Duration>,
+    /// Play every deal once from each seat, so each agent gets the same cards in the same
+    /// position as the others and the luck of the deal cancels out.
+    pub duplicate: bool,
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            starting_stack: 200,
+            hands: 10_000,
+            time_budget: None,
+            duplicate: false,
+            seed: 0,
+        }
+    }
+}
+
+/// The chips won by one agent over a [simulate] run.
+///
+/// The confidence interval comes from the variance of the winnings per deal, which is a set of
+/// mirrored hands when playing duplicate and a single hand otherwise.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentResult {
+    hands: u64,
+    winnings: i64,
+    showdown: i64,
+    big_blind: u32,
+    deals: u64,
+    deal_big_blinds: f64,
+    deal_big_blinds_squared: f64,
+}
+
+impl AgentResult {
+    pub fn hands(&self) -> u64 {
+        self.hands
+    }
+
+    pub fn winnings(&self) -> i64 {
+        self.winnings
+    }
+
+    /// The chips won in hands where the agent went to showdown.
+    pub fn showdown_winnings(&self) -> i64 {
+        self.showdown
+    }
+
+    /// The chips won in hands that were over before the agent showed its cards.
+    pub fn non_showdown_winnings(&self) -> i64 {
+        self.winnings - self.showdown
+    }
+
+    /// Big blinds won per 100 hands.
+    pub fn bb_per_100(&self) -> f64 {
+        100.0 * self.winnings as f64 / self.big_blind.max(1) as f64 / self.hands.max(1) as f64
+    }
+
+    /// The standard error of [AgentResult::bb_per_100].
+    pub fn std_error(&self) -> f64 {
+        if self.deals < 2 {
+            return f64::INFINITY;
+        }
+
+        let deals = self.deals as f64;
+        let mean = self.deal_big_blinds / deals;
+        let variance = (self.deal_big_blinds_squared / deals - mean * mean).max(0.0);
+        100.0 * (variance * deals / (deals - 1.0)).sqrt() * deals.sqrt() / self.hands as f64
+    }
+
+    /// The 95% confidence interval of [AgentResult::bb_per_100].
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.std_error();
+        (self.bb_per_100() - margin, self.bb_per_100() + margin)
+    }
+
+    fn record_deal(&mut self, winnings: i64) {
+        let big_blinds = winnings as f64 / self.big_blind.max(1) as f64;
+        self.deals += 1;
+        self.deal_big_blinds += big_blinds;
+        self.deal_big_blinds_squared += big_blinds * big_blinds;
+    }
+}
+
+/// Play hands between `agents`, one per seat, and count what every agent wins.
+///
+/// Every hand is played at a fresh table with the button on seat 0 and the agents moved one seat
+/// further for every hand, so each agent plays every position equally often. With
+/// [SimulationConfig::duplicate] a deal is played as many times as there are agents before the
+/// next deal, otherwise every hand gets a new deal. Runs stop after [SimulationConfig::hands]
+/// hands or once the time budget is used up, always after a complete deal.
+pub fn simulate(
+    agents: &mut [Box<dyn Agent>],
+    config: &SimulationConfig,
+) -> Result<Vec<AgentResult>, GameError> {
+    let start = Instant::now();
+    let mut rng = Xoshiro256::from_seed(config.seed);
+    let seats = agents.len();
+    let mut results = vec![
+        AgentResult {
+            big_blind: config.game.big_blind,
+            ..AgentResult::default()
+        };
+        seats
+    ];
+
+    let repeats = if config.duplicate { seats as u64 } else { 1 };
+    let mut hands = 0;
+    while hands < config.hands {
+        if config
+            .time_budget
+            .is_some_and(|budget| start.elapsed() >= budget)
+        {
+            break;
+        }
+
+        let seed = rng.next_u64();
+        let mut deal_winnings = vec![0; seats];
+        for _ in 0..repeats {
+            let shift = (hands % seats as u64) as usize;
+            let mut game = Game::new(GameConfig {
+                seed,
+                ..config.game.clone()
+            });
+            for seat in 0..seats {
+                game.add_player(Player::new(format!("seat {}", seat), config.starting_stack));
+            }
+
+            // Agent `i` takes seat `i + shift`, and is put back in place even on an error.
+            agents.rotate_right(shift);
+            let played = game.play_hand(agents);
+            agents.rotate_left(shift);
+            played?;
+
+            let showdown: Vec<usize> = game
+                .history()
+                .iter()
+                .filter_map(|event| match event {
+                    GameEvent::Showdown { seat, .. } => Some(*seat),
+                    _ => None,
+                })
+                .collect();
+
+            for (seat, player) in game.players().iter().enumerate() {
+                let agent = (seat + seats - shift) % seats;
+                let winnings = player.stack() as i64 - config.starting_stack as i64;
+
+                let result = &mut results[agent];
+                result.hands += 1;
+                result.winnings += winnings;
+                if showdown.contains(&seat) {
+                    result.showdown += winnings;
+                }
+                deal_winnings[agent] += winnings;
+            }
+            hands += 1;
+        }
+
+        for (result, winnings) in results.iter_mut().zip(deal_winnings) {
+            result.record_deal(winnings);
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::agent::{CallingStation, RandomAgent};
+
+    use super::*;
+
+    fn config(hands: u64, duplicate: bool) -> SimulationConfig {
+        SimulationConfig {
+            game: GameConfig {
+                small_blind: 5,
+                big_blind: 10,
+                ..GameConfig::default()
+            },
+            starting_stack: 1000,
+            hands,
+            duplicate,
+            seed: 5,
+            ..SimulationConfig::default()
+        }
+    }
+
+    fn agents() -> Vec<Box<dyn Agent>> {
+        vec![
+            Box::new(RandomAgent::from_seed(1)),
+            Box::new(CallingStation),
+            Box::new(RandomAgent::from_seed(2)),
+        ]
+    }
+
+    #[test]
+    fn test_chips_are_conserved() {
+        let results = simulate(&mut agents(), &config(300, false)).unwrap();
+
+        assert_eq!(0, results.iter().map(AgentResult::winnings).sum::<i64>());
+        for result in results.iter() {
+            assert_eq!(300, result.hands());
+            assert_eq!(
+                result.winnings(),
+                result.showdown_winnings() + result.non_showdown_winnings()
+            );
+            let (low, high) = result.confidence_interval();
+            assert!(low <= result.bb_per_100() && result.bb_per_100() <= high);
+        }
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let first = simulate(&mut agents(), &config(100, true)).unwrap();
+        let second = simulate(&mut agents(), &config(100, true)).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(102, first[0].hands());
+    }
+
+    #[test]
+    fn test_duplicate_cancels_luck() {
+        let mut agents: Vec<Box<dyn Agent>> =
+            vec![Box::new(CallingStation), Box::new(CallingStation)];
+
+        // The same strategy with the same cards wins exactly as much as it loses.
+        let results = simulate(&mut agents, &config(200, true)).unwrap();
+        for result in results.iter() {
+            assert_eq!(0, result.winnings());
+            assert_eq!(0.0, result.std_error());
+        }
+
+        let results = simulate(&mut agents, &config(200, false)).unwrap();
+        assert_ne!(0, results[0].winnings());
+        assert!(results[0].std_error() > 0.0);
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let results = simulate(
+            &mut agents(),
+            &SimulationConfig {
+                hands: u64::MAX,
+                time_budget: Some(Duration::from_millis(50)),
+                ..config(0, false)
+            },
+        )
+        .unwrap();
+        assert!(results[0].hands() > 0);
+    }
+}
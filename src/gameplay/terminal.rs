@@ -0,0 +1,278 @@
+use std::io::{self, BufRead, Write};
+
+use super::action::{Action, ActionError, LegalActions};
+use super::agent::{Agent, Observation};
+use super::game::{Game, GameError, GameEvent};
+
+/// Play hands at `game` until the input ends, `quit` is typed or fewer than two players have
+/// chips left.
+///
+/// Seats with an agent in `seats` play by themselves, the others are typed in through `input` as
+/// actions like `call`, `raise 300` or `fold`. Everything that happens is written to `output`,
+/// with the hole cards of the typed in seats only until the showdown.
+pub fn play(
+    game: &mut Game,
+    seats: &mut [Option<Box<dyn Agent>>],
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    loop {
+        match game.new_round() {
+            Err(GameError::NotEnoughPlayers) => {
+                writeln!(output, "Not enough players with chips left.")?;
+                return Ok(());
+            }
+            result => result.map_err(io::Error::other)?,
+        }
+
+        let mut printed = game
+            .history()
+            .iter()
+            .rposition(|event| matches!(event, GameEvent::NewHand { .. }))
+            .unwrap_or_default();
+
+        while let Some(observation) = game.observe() {
+            print_events(game, seats, &mut printed, &mut output)?;
+
+            let seat = observation.seat();
+            let action = match seats.get_mut(seat) {
+                Some(Some(agent)) => agent.act(&observation),
+                _ => match prompt(&observation, &mut input, &mut output)? {
+                    Some(action) => action,
+                    None => return Ok(()),
+                },
+            };
+            game.act(seat, action).map_err(io::Error::other)?;
+        }
+
+        print_events(game, seats, &mut printed, &mut output)?;
+        print_summary(game, &mut output)?;
+    }
+}
+
+/// Ask for an action until a legal one is typed, `None` once the player quits.
+fn prompt(
+    observation: &Observation,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Action>> {
+    let legal = observation.legal_actions();
+    writeln!(
+        output,
+        "Board {}, pot {}, your stack {}, your hand {}",
+        observation.board(),
+        observation.pot(),
+        observation.stack(),
+        observation.hole_cards()
+    )?;
+    writeln!(output, "{}", options(legal, observation.stack()))?;
+
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 || line.trim() == "quit" {
+            return Ok(None);
+        }
+
+        let action = line
+            .parse::<Action>()
+            .and_then(|action| legal.check(action).map(|_| action));
+        match action {
+            Ok(action) => return Ok(Some(action)),
+            Err(error) => writeln!(output, "{}", explain(&error))?,
+        }
+    }
+}
+
+fn options(legal: &LegalActions, stack: u32) -> String {
+    let mut options = vec!["fold".to_string()];
+    if legal.can_check() {
+        options.push("check".to_string());
+    } else {
+        options.push(format!("call {}", legal.to_call().min(stack)));
+    }
+    if let Some((min, max)) = legal.bet_range() {
+        options.push(format!("bet {}-{}", min, max));
+    }
+    if let Some((min, max)) = legal.raise_range() {
+        options.push(format!("raise {}-{}", min, max));
+    }
+    if let Some(total) = legal.all_in() {
+        options.push(format!("all-in {}", total));
+    }
+    options.push("quit".to_string());
+    format!("You can {}", options.join(", "))
+}
+
+fn explain(error: &ActionError) -> String {
+    match error {
+        ActionError::InvalidNotation(typed) => {
+            format!(
+                "Unknown action '{}', try 'call' or 'raise 300'",
+                typed.trim()
+            )
+        }
+        ActionError::CannotCheck { to_call } => format!("Cannot check, {} to call", to_call),
+        ActionError::NothingToCall => "Nothing to call, check instead".to_string(),
+        ActionError::BetNotAllowed => "Cannot bet, raise instead".to_string(),
+        ActionError::RaiseNotAllowed => "Cannot raise here".to_string(),
+        ActionError::RaiseNotReopened => {
+            "Only an incomplete raise came in, call or fold".to_string()
+        }
+        ActionError::AllInNotAllowed => "Cannot go all-in here".to_string(),
+        ActionError::BetOutOfRange { min, max, .. } => {
+            format!("Bet between {} and {}", min, max)
+        }
+        ActionError::RaiseOutOfRange { min, max, .. } => {
+            format!("Raise to between {} and {}", min, max)
+        }
+        ActionError::OutOfTurn { .. } => "It is not your turn".to_string(),
+    }
+}
+
+/// Write the events after `printed`, leaving showdowns and wins to [print_summary].
+fn print_events(
+    game: &Game,
+    seats: &[Option<Box<dyn Agent>>],
+    printed: &mut usize,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let name = |seat: &usize| game.players()[*seat].name();
+
+    for event in game.history()[*printed..].iter() {
+        match event {
+            GameEvent::NewHand { dealer, betting } => {
+                writeln!(
+                    output,
+                    "--- New hand of {}, {} has the button ---",
+                    betting,
+                    name(dealer)
+                )?;
+            }
+            GameEvent::Blind { seat, amount } => {
+                writeln!(output, "{} posts a blind of {}", name(seat), amount)?
+            }
+            GameEvent::Ante { seat, amount } => {
+                writeln!(output, "{} posts an ante of {}", name(seat), amount)?
+            }
+            GameEvent::Straddle { seat, amount } => {
+                writeln!(output, "{} straddles {}", name(seat), amount)?
+            }
+            GameEvent::MissedBlind { seat, amount } => {
+                writeln!(output, "{} misses a blind of {}", name(seat), amount)?
+            }
+            GameEvent::DeadBlind { seat, amount } => {
+                writeln!(output, "{} posts a dead blind of {}", name(seat), amount)?
+            }
+            GameEvent::HoleCards { seat, cards } => {
+                if seats.get(*seat).is_none_or(Option::is_none) {
+                    writeln!(output, "{} is dealt {}", name(seat), cards)?;
+                }
+            }
+            GameEvent::Action { seat, action } => writeln!(output, "{}: {}", name(seat), action)?,
+            GameEvent::Flop(cards) => writeln!(output, "Flop {}", cards)?,
+            GameEvent::Turn(cards) => writeln!(output, "Turn {}", cards)?,
+            GameEvent::River(cards) => writeln!(output, "River {}", cards)?,
+            GameEvent::UncalledBet { seat, amount } => {
+                writeln!(output, "{} takes back {} nobody called", name(seat), amount)?
+            }
+            GameEvent::Showdown { .. } | GameEvent::Win { .. } => {}
+        }
+    }
+
+    *printed = game.history().len();
+    Ok(())
+}
+
+/// Write who showed what and who won how much in the last hand, and the stacks after it.
+fn print_summary(game: &Game, output: &mut impl Write) -> io::Result<()> {
+    let name = |seat: &usize| game.players()[*seat].name();
+    let start = game
+        .history()
+        .iter()
+        .rposition(|event| matches!(event, GameEvent::NewHand { .. }))
+        .unwrap_or_default();
+
+    writeln!(output, "--- Board {} ---", game.board())?;
+    for event in game.history()[start..].iter() {
+        match event {
+            GameEvent::Showdown { seat, outcome } => writeln!(
+                output,
+                "{} shows {}: {}",
+                name(seat),
+                game.hole_cards(*seat),
+                outcome
+            )?,
+            GameEvent::Win { seat, amount } => writeln!(output, "{} wins {}", name(seat), amount)?,
+            _ => {}
+        }
+    }
+    let stacks: Vec<String> = game
+        .players()
+        .iter()
+        .map(|player| format!("{} {}", player.name(), player.stack()))
+        .collect();
+    writeln!(output, "Stacks: {}", stacks.join(", "))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::agent::CallingStation;
+    use crate::gameplay::game::GameConfig;
+    use crate::gameplay::player::Player;
+
+    use super::*;
+
+    fn heads_up() -> (Game, Vec<Option<Box<dyn Agent>>>) {
+        let mut game = Game::new(GameConfig {
+            small_blind: 5,
+            big_blind: 10,
+            seed: 2,
+            ..GameConfig::default()
+        });
+        game.add_player(Player::new("you".to_string(), 1000));
+        game.add_player(Player::new("bot".to_string(), 1000));
+        (game, vec![None, Some(Box::new(CallingStation))])
+    }
+
+    #[test]
+    fn test_play_to_showdown() {
+        let (mut game, mut seats) = heads_up();
+        let input = "raise 5\nshove\ncall\ncheck\nbet 20\ncheck\ncheck\nquit\n";
+        let mut output = vec![];
+
+        play(&mut game, &mut seats, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("You can fold, call 5, raise 20-1000, all-in 1000, quit"));
+        assert!(output.contains("Raise to between 20 and 1000"));
+        assert!(output.contains("Unknown action 'shove'"));
+        assert!(output.contains("you is dealt ["));
+        assert!(!output.contains("bot is dealt"));
+        assert!(output.contains("bot: call"));
+
+        // Both hands are shown with their outcome, the bot's hole cards only then.
+        assert_eq!(2, output.matches(" shows ").count(), "{}", output);
+        assert!(output.contains(" wins "));
+        assert!(output.contains("--- New hand of no-limit, bot has the button ---"));
+        assert_eq!(
+            2000,
+            game.players().iter().map(Player::stack).sum::<u32>() + game.pot()
+        );
+    }
+
+    #[test]
+    fn test_quit_at_end_of_input() {
+        let (mut game, mut seats) = heads_up();
+        let mut output = vec![];
+
+        play(&mut game, &mut seats, "fold\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("you: fold"));
+        assert!(output.contains("bot takes back 5 nobody called"));
+        assert!(output.contains("bot wins 10"));
+    }
+}
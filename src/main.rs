@@ -1,7 +1,257 @@
-mod engine;
-mod gameplay;
-mod util;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-fn main() {
-    println!("Hello, world!");
+use anyhow::{bail, Context, Result};
+
+use nutcracker::engine::cards::Cards;
+use nutcracker::engine::monte_carlo::{sample_ranges_parallel, SampleConfig};
+use nutcracker::engine::out_runner::{runout_multiway_parallel, runout_ranges_parallel};
+use nutcracker::engine::range::Range;
+use nutcracker::gameplay::agent::{Agent, EquityAgent, EquityConfig};
+use nutcracker::gameplay::game::{Game, GameConfig};
+use nutcracker::gameplay::player::Player;
+use nutcracker::gameplay::terminal;
+
+const USAGE: &str = "Usage:
+  nutcracker play [--bots COUNT] [--stack CHIPS] [--blinds SMALL/BIG] [--seed SEED]
+  nutcracker equity HAND_OR_RANGE... [--board CARDS] [--dead CARDS] [--exact | --samples TRIALS]
+                    [--seed SEED] [--threads COUNT] [--json]
+
+Equity counts every runout exactly when every player holds one hand or the flop is out, and
+samples otherwise. Ranges look like QQ+,AKs,A5s-A2s,KQo:0.5 and hands like AsKs.";
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("play") => play(&args[1..]),
+        Some("equity") => equity(&args[1..]),
+        _ => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+/// Play against bots in the terminal, the human always sits in seat 0.
+fn play(args: &[String]) -> Result<()> {
+    let mut bots = 2;
+    let mut stack = 1000;
+    let mut config = GameConfig {
+        small_blind: 5,
+        big_blind: 10,
+        seed: SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+        ..GameConfig::default()
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .with_context(|| format!("{} needs a value\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--bots" => bots = value.parse()?,
+            "--stack" => stack = value.parse()?,
+            "--blinds" => {
+                let (small, big) = value
+                    .split_once('/')
+                    .with_context(|| format!("blinds look like 5/10, not {}", value))?;
+                config.small_blind = small.parse()?;
+                config.big_blind = big.parse()?;
+            }
+            "--seed" => config.seed = value.parse()?,
+            _ => bail!("unknown option {}\n{}", flag, USAGE),
+        }
+    }
+    if !(1..=9).contains(&bots) {
+        bail!("play against 1 to 9 bots, not {}", bots);
+    }
+
+    let seed = config.seed;
+    let mut game = Game::new(config);
+    game.add_player(Player::new("hero".to_string(), stack));
+    let mut seats: Vec<Option<Box<dyn Agent>>> = vec![None];
+    for bot in 1..=bots {
+        game.add_player(Player::new(format!("bot {}", bot), stack));
+        seats.push(Some(Box::new(EquityAgent::new(EquityConfig {
+            seed: seed.wrapping_add(bot),
+            ..EquityConfig::default()
+        }))));
+    }
+
+    terminal::play(&mut game, &mut seats, io::stdin().lock(), io::stdout())?;
+    Ok(())
+}
+
+/// The equity of one player, with the standard error when sampled.
+struct Row {
+    hand: String,
+    equity: f64,
+    win: f64,
+    tie: f64,
+    std_error: Option<f64>,
+}
+
+/// Calculate the equity of hands and ranges against each other, like
+/// `nutcracker equity AsKs QQ+ --board 7h8h2d --dead 2c`.
+fn equity(args: &[String]) -> Result<()> {
+    let mut hands: Vec<String> = vec![];
+    let mut board = Cards::default();
+    let mut dead = Cards::default();
+    let mut exact = None;
+    let mut config = SampleConfig::default();
+    let mut threads = 0;
+    let mut json = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("{} needs a value\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--board" => board = value()?.parse()?,
+            "--dead" => dead = value()?.parse()?,
+            "--exact" => exact = Some(true),
+            "--samples" => {
+                exact = Some(false);
+                config.max_trials = value()?.parse()?;
+                if config.max_trials < 2 {
+                    bail!("sample at least 2 trials to get a standard error");
+                }
+            }
+            "--seed" => config.seed = value()?.parse()?,
+            "--threads" => threads = value()?.parse()?,
+            "--json" => json = true,
+            flag if flag.starts_with("--") => bail!("unknown option {}\n{}", flag, USAGE),
+            hand => hands.push(hand.to_string()),
+        }
+    }
+    if hands.len() < 2 {
+        bail!("give at least two hands or ranges\n{}", USAGE);
+    }
+
+    let ranges = hands
+        .iter()
+        .map(|hand| hand.parse::<Range>())
+        .collect::<Result<Vec<Range>, _>>()?;
+    let combos: Option<Vec<Cards>> = ranges
+        .iter()
+        .map(
+            |range| match range.combos().collect::<Vec<_>>().as_slice() {
+                [(combo, _)] => Some(*combo),
+                _ => None,
+            },
+        )
+        .collect();
+    let exact = exact.unwrap_or(combos.is_some() || board.card_count() >= 3);
+
+    let rows: Vec<Row> = if !exact {
+        sample_ranges_parallel(&ranges, board, dead, &config, threads)?
+            .iter()
+            .zip(hands)
+            .map(|(estimate, hand)| Row {
+                hand,
+                equity: estimate.equity(),
+                win: estimate.win(),
+                tie: estimate.tie(),
+                std_error: Some(estimate.std_error()),
+            })
+            .collect()
+    } else {
+        let equities = match combos {
+            Some(combos) => runout_multiway_parallel(&combos, board, dead, threads)?,
+            None => runout_ranges_parallel(&ranges, board, dead, threads)?
+                .iter()
+                .map(|range| range.equity())
+                .collect(),
+        };
+        equities
+            .iter()
+            .zip(hands)
+            .map(|(equity, hand)| Row {
+                hand,
+                equity: equity.equity(),
+                win: equity.win(),
+                tie: equity.tie(),
+                std_error: None,
+            })
+            .collect()
+    };
+
+    if json {
+        print_json(&rows, board, dead, exact);
+    } else {
+        print_table(&rows, board, dead, exact);
+    }
+    Ok(())
+}
+
+fn print_table(rows: &[Row], board: Cards, dead: Cards, exact: bool) {
+    let mode = if exact { "exact" } else { "sampled" };
+    println!("Board {}, dead {}, {}", board, dead, mode);
+
+    let width = rows
+        .iter()
+        .map(|row| row.hand.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    println!(
+        "{:width$}  {:>7}  {:>7}  {:>7}",
+        "Hand", "Equity", "Win", "Tie"
+    );
+    for row in rows {
+        print!(
+            "{:width$}  {:>6.2}%  {:>6.2}%  {:>6.2}%",
+            row.hand,
+            100.0 * row.equity,
+            100.0 * row.win,
+            100.0 * row.tie
+        );
+        match row.std_error {
+            Some(std_error) => println!("  ±{:.2}%", 196.0 * std_error),
+            None => println!(),
+        }
+    }
+}
+
+fn print_json(rows: &[Row], board: Cards, dead: Cards, exact: bool) {
+    let string = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let cards = |cards: Cards| {
+        string(
+            &cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect::<String>(),
+        )
+    };
+
+    // JSON has no infinity or NaN, write those as null like a missing standard error.
+    let number = |value: Option<f64>| match value {
+        Some(value) if value.is_finite() => value.to_string(),
+        _ => "null".to_string(),
+    };
+
+    let players: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{{\"hand\":{},\"equity\":{},\"win\":{},\"tie\":{},\"std_error\":{}}}",
+                string(&row.hand),
+                number(Some(row.equity)),
+                number(Some(row.win)),
+                number(Some(row.tie)),
+                number(row.std_error)
+            )
+        })
+        .collect();
+
+    println!(
+        "{{\"board\":{},\"dead\":{},\"exact\":{},\"players\":[{}]}}",
+        cards(board),
+        cards(dead),
+        exact,
+        players.join(",")
+    );
 }
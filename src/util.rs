@@ -1,2 +1,4 @@
 pub mod all_bit_combo_iterator;
 pub mod bit_iterator;
+pub mod combinatorics;
+pub mod rng;
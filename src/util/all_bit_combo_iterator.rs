@@ -1,8 +1,13 @@
 use super::bit_iterator::IntoFromRightBitIterator;
+use super::combinatorics::choose;
 
+/// Iterates over every combination of a fixed amount of 1-bits of a mask, see [IntoAllBitIterator].
 pub struct AllBitIterator {
     mask: u64,
     next: u64,
+    remaining: u64,
+    /// `lowest[i]` holds the lowest `i` 1-bits of `mask`, up to the combination size.
+    lowest: [u64; 64],
 }
 
 pub trait IntoAllBitIterator {
@@ -12,29 +17,33 @@ pub trait IntoAllBitIterator {
 impl IntoAllBitIterator for u64 {
     /// Iterate over all the combination of bits set to 1 of size 'combo_size'.
     ///
-    /// Uses a modified version of [gospers algorithm](https://programmingforinsomniacs.blogspot.com/2018/03/gospers-hack-explained.html).
+    /// Uses a modified version of [gospers algorithm](https://programmingforinsomniacs.blogspot.com/2018/03/gospers-hack-explained.html)
+    /// that works directly on the bits of the mask, every step takes constant time.
     ///
     /// ### Steps
     /// 1. Find the rightmost 1-bit that can be moved left into a 0-bit. Move that 1-bit left one position.
     /// 2. Move all 1-bits that are to the right of that bit all the way to the right.
     ///
-    ///
+    /// A `combo_size` of 0 yields the empty combination once, a `combo_size` larger than the
+    /// amount of 1-bits yields nothing.
     fn iter_all_combos(self, combo_size: usize) -> AllBitIterator {
-        if combo_size > self.count_ones() as usize {
-            panic!(
-                "combo_size ({combo_size}) larger than the amount of 1-bits {}",
-                self.count_ones()
-            );
+        let mut lowest = [0; 64];
+        for (i, bit) in self.iter_from_right().enumerate().take(combo_size.min(63)) {
+            lowest[i + 1] = lowest[i] | bit;
         }
 
-        let current: u64 = self
-            .iter_from_right()
-            .take(combo_size)
-            .fold(0, |acc, cur| acc | cur);
+        let remaining = choose(self.count_ones() as u64, combo_size as u64);
+        let next = if combo_size == 64 {
+            self
+        } else {
+            lowest[combo_size.min(63)]
+        };
 
         AllBitIterator {
             mask: self,
-            next: current,
+            next,
+            remaining,
+            lowest,
         }
     }
 }
@@ -43,51 +52,36 @@ impl Iterator for AllBitIterator {
     type Item = u64;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
         let current = self.next;
+        self.remaining -= 1;
 
-        let mut cur_bit_p: Option<u64> = None;
-        for (i, next_bit_p) in self.mask.iter_from_right().enumerate() {
-            if let Some(cur_bit_p) = cur_bit_p {
-                if cur_bit_p & self.next != 0 && next_bit_p & self.next == 0 {
-                    // Here we know that bit_p is pointing to a bit in self.current that is filled,
-                    // and we know that next_bit_p is pointing to the next bit in in self.current, and that it's empty.
-
-                    // So we set next_bit_p to 1
-                    self.next |= next_bit_p;
-                    // and cur_bit_p to 0;
-                    self.next ^= cur_bit_p;
-
-                    let right_of_moved = self
-                        .mask
-                        .iter_from_right()
-                        .take(i - 1)
-                        .filter(|n| n & self.next != 0)
-                        .count();
-
-                    if right_of_moved > 0 {
-                        for (i, n) in self.mask.iter_from_right().enumerate().take(i - 1) {
-                            self.next |= n;
-                            if i >= right_of_moved {
-                                self.next ^= n;
-                            }
-                        }
-                    }
-
-                    return Some(current);
-                }
-            }
-            cur_bit_p = Some(next_bit_p);
-        }
+        if self.remaining > 0 {
+            // Step 1: add the lowest 1-bit, with the bits outside the mask set so the carry skips
+            // them. The carry clears the lowest run of 1-bits and sets the next free bit above it.
+            let lowest_bit = current & current.wrapping_neg();
+            let moved = (current | !self.mask).wrapping_add(lowest_bit) & self.mask;
 
-        if current != 0 {
-            self.next = 0;
-            Some(current)
-        } else {
-            None
+            // Step 2: all but one of the cleared bits go back to the bottom of the mask.
+            let cleared = (current & !moved).count_ones() as usize;
+            self.next = moved | self.lowest[cleared - 1];
         }
+
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
     }
 }
 
+impl ExactSizeIterator for AllBitIterator {}
+
+impl std::iter::FusedIterator for AllBitIterator {}
+
 #[cfg(test)]
 mod tests {
     use std::time::Instant;
@@ -110,6 +104,7 @@ mod tests {
     }
 
     #[test]
+    #[ignore = "benchmark, iterates every hand and table"]
     fn test_all_bit_iter() {
         let instant = Instant::now();
 
@@ -135,6 +130,53 @@ mod tests {
         assert_eq!(total, iter.count() as u128);
     }
 
+    #[test]
+    fn test_matches_subsets() {
+        let mask = 0b1011_0110_1001u64;
+        let bits: Vec<u64> = mask.iter_from_right().collect();
+
+        for size in 0..=bits.len() {
+            let combos: Vec<u64> = mask.iter_all_combos(size).collect();
+
+            let mut expected: Vec<u64> = (0..1u64 << bits.len())
+                .filter(|subset| subset.count_ones() as usize == size)
+                .map(|subset| {
+                    (0..bits.len())
+                        .filter(|i| subset & (1 << i) != 0)
+                        .fold(0, |combo, i| combo | bits[i])
+                })
+                .collect();
+            expected.sort_unstable();
+
+            // Combinations come in colex order, which is ascending order of their values.
+            assert_eq!(expected, combos, "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_edge_sizes() {
+        assert_eq!(vec![0], FULL_DECK.iter_all_combos(0).collect::<Vec<_>>());
+        assert_eq!(vec![0], 0u64.iter_all_combos(0).collect::<Vec<_>>());
+        assert_eq!(0, 0b101u64.iter_all_combos(3).count());
+        assert_eq!(vec![0b101], 0b101u64.iter_all_combos(2).collect::<Vec<_>>());
+        assert_eq!(
+            vec![u64::MAX],
+            u64::MAX.iter_all_combos(64).collect::<Vec<_>>()
+        );
+        assert_eq!(64, u64::MAX.iter_all_combos(63).count());
+        assert_eq!(0, u64::MAX.iter_all_combos(65).count());
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut iter = FULL_DECK.iter_all_combos(2);
+        assert_eq!(1326, iter.len());
+
+        iter.nth(25);
+        assert_eq!((1300, Some(1300)), iter.size_hint());
+        assert_eq!(1300, iter.count());
+    }
+
     #[test]
     fn test_perf() {
         let hand = (ACE & SPADE) | (ACE & HEART);
@@ -145,7 +187,7 @@ mod tests {
         for table in deck.value().iter_all_combos(5) {
             for _ in (deck.value() ^ table).iter_all_combos(2) {
                 i += 1;
-                if i % 10000000 == 0 {
+                if i.is_multiple_of(10000000) {
                     println!("Elapsed {} {}", i, instant.elapsed().as_secs_f32());
                     instant = Instant::now();
                 }
@@ -0,0 +1,29 @@
+/// The amount of ways to pick `k` out of `n`.
+pub(crate) const fn choose(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+
+    let k = if k < n - k { k } else { n - k };
+    let mut result = 1u128;
+    let mut i = 0;
+    while i < k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        i += 1;
+    }
+    result as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose() {
+        assert_eq!(1, choose(0, 0));
+        assert_eq!(0, choose(2, 3));
+        assert_eq!(1326, choose(52, 2));
+        assert_eq!(133_784_560, choose(52, 7));
+        assert_eq!(choose(52, 5), choose(52, 47));
+    }
+}
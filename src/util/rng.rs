@@ -0,0 +1,100 @@
+/// A source of random numbers, implement it to plug another generator into the engine.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// A uniform random number in `0..bound`, `bound` must not be 0.
+    ///
+    /// Uses [Lemire's method](https://arxiv.org/abs/1805.10941) to avoid the bias of a plain modulo.
+    fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound != 0, "bound must not be 0");
+
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = self.next_u64() as u128 * bound as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+
+    /// A uniform random number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The [xoshiro256**](https://prng.di.unimi.it/) generator, small, fast and reproducible from a seed.
+#[derive(Debug, Clone)]
+pub struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    /// Expand `seed` into the full state with splitmix64, so every seed gives a usable state.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut state = [0; 4];
+        for part in state.iter_mut() {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            *part = z ^ (z >> 31);
+        }
+
+        Xoshiro256 { state }
+    }
+}
+
+impl Rng for Xoshiro256 {
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= shifted;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let mut a = Xoshiro256::from_seed(42);
+        let mut b = Xoshiro256::from_seed(42);
+        let mut c = Xoshiro256::from_seed(43);
+
+        let a: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+        let c: Vec<u64> = (0..10).map(|_| c.next_u64()).collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_below() {
+        let mut rng = Xoshiro256::from_seed(7);
+        let mut counts = [0u32; 6];
+        for _ in 0..60000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+
+        for count in counts {
+            assert!((9000..11000).contains(&count), "{:?}", counts);
+        }
+
+        for _ in 0..1000 {
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+    }
+}